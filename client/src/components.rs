@@ -1,5 +1,6 @@
 use crate::resources::MyPlayer;
 use bevy::prelude::*;
+use protocol::messages::Stance;
 
 #[derive(Component)]
 pub struct LocalPlayer;
@@ -7,6 +8,18 @@ pub struct LocalPlayer;
 #[derive(Component)]
 pub struct PlayerMarker(pub u64);
 
+// Стойка чужого игрока из снапшота; свою берём из CurrentStance, без задержки сервера
+#[derive(Component, PartialEq)]
+pub struct PlayerStance(pub Stance);
+
+// Обводка под спрайтом игрока (дочерняя сущность), цвет — по стойке
+#[derive(Component)]
+pub struct StanceOutline;
+
+// Ник над игроком (дочерний к HP-тексту)
+#[derive(Component)]
+pub struct NameTag(pub u64);
+
 #[derive(Component)]
 pub struct Bullet {
     pub ttl: f32,
@@ -16,6 +29,10 @@ pub struct Bullet {
 #[derive(Component)]
 pub struct Health(pub i32);
 
+// Аптечка или броня на карте; номер — индекс в GameMap::pickups
+#[derive(Component)]
+pub struct PickupMarker(pub usize);
+
 /// Компонент «летящая граната»
 #[derive(Component)]
 pub struct Grenade {
@@ -1,4 +1,8 @@
 use bevy::prelude::*;
 
 pub const BULLET_SPEED: f32 = 10000.0; // 0.35;
-pub const BULLET_TTL: f32 = 1.0; // 900.0;
+pub const KILL_FEED_TTL: f32 = 5.0; // сколько висит строка килфида, secs
+pub const KILL_FEED_FADE: f32 = 1.0; // последние секунды — затухание
+pub const KILL_FEED_MAX: usize = 5;
+pub const STANCE_OUTLINE: f32 = 3.0; // толщина обводки стойки, px
+pub const MAX_GRENADE_CATCHUP: u32 = 64; // шагов симуляции гранаты за кадр, не больше
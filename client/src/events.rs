@@ -1,11 +1,12 @@
 use bevy::prelude::*;
-use protocol::messages::GrenadeEvent;
+use protocol::messages::{DeathCause, GrenadeEvent};
 
 /// Дискретное событие «игрок погиб»
 #[derive(Event)]
 pub struct PlayerDied {
     pub victim: u64,
     pub killer: Option<u64>,
+    pub cause: DeathCause,
 }
 
 #[derive(Event)]
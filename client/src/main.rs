@@ -20,19 +20,28 @@ use protocol::messages::Stance;
 use resources::*;
 use systems::{
     bullet_lifecycle::bullet_lifecycle,
-    connection::handle_connection_event,
+    connection::{handle_connection_event, send_hello_on_connect},
     grenade_lifecycle::explosion_lifecycle, // grenade_lifecycle::grenade_lifecycle,
     grenade_throw::grenade_throw,
     input::change_stance,
     interpolate_with_snapshot::interpolate_with_snapshot,
     network::receive_server_messages,
+    pickups::sync_pickup_visibility,
     ping::send_ping,
     rotate_to_cursor::rotate_to_cursor,
     send_input::send_input_and_predict,
-    shoot::shoot_mouse,
+    shoot::{reload_weapon, shoot_mouse, switch_weapon},
+    stance_visuals::apply_stance_visuals,
     startup::setup,
 };
-use ui::update_grenade_cooldown_ui::update_grenade_cooldown_ui;
+use ui::{
+    ammo_ui::{setup_ammo_ui, update_ammo_ui},
+    armor_ui::{setup_armor_ui, update_armor_ui},
+    kill_feed_ui::{push_kill_feed, setup_kill_feed_ui, update_kill_feed},
+    round_ui::{setup_round_ui, update_round_ui},
+    scoreboard_ui::{setup_scoreboard_ui, toggle_scoreboard_ui, update_scoreboard_ui},
+    update_grenade_cooldown_ui::update_grenade_cooldown_ui,
+};
 
 use crate::{
     app_state::AppState,
@@ -40,11 +49,17 @@ use crate::{
         GrenadeDetonatedEvent, GrenadeSpawnEvent, PlayerDamagedEvent, PlayerDied, PlayerLeftEvent,
     },
     menu::{clear_connect_timeout, connection_timeout_system, MenuPlugin},
-    resources::grenades::{ClientGrenades, GrenadeCooldown, GrenadeStates},
+    resources::{
+        grenades::{ClientGrenades, GrenadeCooldown, GrenadeStates},
+        rounds::{PlayerTeams, RoundHud},
+        scoreboard::Scoreboard,
+        weapons::{AmmoState, FireCooldown, ShotCounter},
+    },
     systems::{
-        // +++ насос Connecting: ждём первый Snapshot, затем -> InGame +++
-        aim::{spawn_aim_marker, update_aim_to_mouse}, camera::CameraFollowPlugin, connecting_pump::connecting_pump, corpse_lc::corpse_lifecycle, ensure_my_id::ensure_my_id_from_conn, grenade_lifecycle::spawn_grenades, level::fill_solid_tiles_once, level_fixed::setup_fixed_level, network::apply_grenade_net, render_detonations::render_detonations, spawn_damage_popups::{spawn_damage_popups, update_damage_popups}, startup::load_ui_font, sync_hp_ui::{
+        // +++ насос Connecting: ждём Welcome, затем -> InGame +++
+        aim::{spawn_aim_marker, update_aim_to_mouse}, camera::CameraFollowPlugin, connecting_pump::connecting_pump, corpse_lc::corpse_lifecycle, ensure_my_id::ensure_my_id_from_conn, grenade_lifecycle::spawn_grenades, level::fill_solid_tiles, level_map::setup_map_level, network::apply_grenade_net, render_detonations::render_detonations, spawn_damage_popups::{spawn_damage_popups, update_damage_popups}, startup::load_ui_font, sync_hp_ui::{
             cleanup_hp_ui_on_player_remove, sync_hp_ui_position, update_hp_text_from_event,
+            update_name_tags,
         }, walls_cache::build_wall_aabb_cache
     },
     ui::grenade_ui::setup_grenade_ui,
@@ -59,20 +74,32 @@ fn main() {
             snapshots: VecDeque::new(),
             delay: 0.05,
         })
+        .insert_resource(SnapshotBaselines::default())
         .insert_resource(CurrentStance(Stance::Standing))
         .insert_resource(SendTimer(Timer::from_seconds(
             TICK_DT,
             TimerMode::Repeating,
         )))
         .insert_resource(SpawnedPlayers::default())
+        .insert_resource(PlayerNames::default())
         .insert_resource(SeqCounter(0))
         .insert_resource(PendingInputsClient::default())
         .insert_resource(HeartbeatTimer::default())
         .insert_resource(ClientLatency::default())
         .insert_resource(DeadPlayers::default())
         .insert_resource(GrenadeCooldown::default())
+        .insert_resource(AmmoState::default())
+        .insert_resource(FireCooldown::default())
+        .insert_resource(ShotCounter::default())
+        .insert_resource(RoundHud::default())
+        .insert_resource(PlayerTeams::default())
+        .insert_resource(Scoreboard::default())
         .insert_resource(HpUiMap::default())
+        .insert_resource(MyArmor::default())
+        .insert_resource(PickupStates::default())
         .insert_resource(SolidTiles::default())
+        .insert_resource(MapCollision::default())
+        .insert_resource(NegotiatedCaps::default())
         .insert_resource(ClientGrenades::default())
         .insert_resource(GrenadeStates::default())
         .insert_resource(WallAabbCache::default())
@@ -101,10 +128,11 @@ fn main() {
         .add_plugins(MenuPlugin)
         // --- шрифты грузим заранее (нужны в меню тоже) ---
         .add_systems(Startup, load_ui_font)
-        // --- Connecting: ждём первый снапшот и следим за таймаутом ---
+        // --- Connecting: шлём Hello, ждём Welcome/Rejected и следим за таймаутом ---
         .add_systems(
             Update,
-            (connecting_pump, connection_timeout_system).run_if(in_state(AppState::Connecting)),
+            (send_hello_on_connect, connecting_pump, connection_timeout_system)
+                .run_if(in_state(AppState::Connecting)),
         )
         // сброс таймера при входе в игру
         .add_systems(OnEnter(AppState::InGame), clear_connect_timeout)
@@ -113,8 +141,12 @@ fn main() {
             OnEnter(AppState::InGame),
             (
                 setup,
-                setup_fixed_level,
                 setup_grenade_ui,
+                setup_ammo_ui,
+                setup_armor_ui,
+                setup_round_ui,
+                setup_scoreboard_ui,
+                setup_kill_feed_ui,
             ),
         )
         // --- PreUpdate: сетка/инпут и приём сообщений только в InGame ---
@@ -130,13 +162,20 @@ fn main() {
                 .chain()
                 .run_if(in_state(AppState::InGame)),
         )
+        // уровень строим, когда сервер сообщил карту (S2C::MapInfo / MapData)
+        .add_systems(
+            Update,
+            setup_map_level.run_if(
+                in_state(AppState::InGame).and(resource_exists_and_changed::<ActiveMap>),
+            ),
+        )
         .add_systems(OnEnter(AppState::InGame), spawn_aim_marker)
         .add_systems(Update, update_aim_to_mouse.run_if(in_state(AppState::InGame)))
         // --- Update: вся игровая логика только в InGame ---
         .add_systems(
             Update,
             (
-                fill_solid_tiles_once,
+                fill_solid_tiles.after(setup_map_level),
                 interpolate_with_snapshot,
                 bullet_lifecycle,
                 // grenades
@@ -148,7 +187,10 @@ fn main() {
                 grenade_throw,
                 rotate_to_cursor,
                 change_stance,
+                apply_stance_visuals,
                 shoot_mouse,
+                reload_weapon,
+                switch_weapon,
                 send_ping,
             )
                 .chain()
@@ -158,12 +200,21 @@ fn main() {
             Update,
             (
                 update_grenade_cooldown_ui,
+                update_ammo_ui,
+                update_armor_ui,
+                update_round_ui,
+                toggle_scoreboard_ui,
+                update_scoreboard_ui,
+                push_kill_feed,
+                update_kill_feed,
                 spawn_damage_popups,
                 update_damage_popups,
                 sync_hp_ui_position,
                 update_hp_text_from_event,
+                update_name_tags,
                 cleanup_hp_ui_on_player_remove,
                 corpse_lifecycle,
+                sync_pickup_visibility,
             )
                 .run_if(in_state(AppState::InGame)),
         )
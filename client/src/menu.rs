@@ -1,8 +1,10 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
 use bevy::prelude::*;
 use bevy::ui::{AlignItems, BackgroundColor, FlexDirection, JustifyContent, Node, UiRect, Val};
 use bevy_quinnet::client::QuinnetClient;
 use bevy_quinnet::client::certificate::CertificateVerificationMode;
 use bevy_quinnet::client::connection::ClientEndpointConfiguration;
+use protocol::constants::NICKNAME_MAX_LEN;
 use protocol::quinnet_adapter::build_channels_config;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
@@ -14,6 +16,17 @@ use crate::resources::CurrentConnId;
 #[derive(Resource, Default, Clone)]
 pub struct ServerAddr(pub String);
 
+#[derive(Resource, Default, Clone)]
+pub struct Nickname(pub String); // уходит серверу в Hello
+
+// какое поле сейчас редактируется
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+enum MenuFocus {
+    #[default]
+    Addr,
+    Nick,
+}
+
 #[derive(Resource, Default)]
 pub struct ConnectError(pub Option<String>); // хранит текст последней ошибки коннекта
 
@@ -27,6 +40,8 @@ struct MenuCamera;
 #[derive(Component)]
 struct AddrValue; // текст набранного адреса (моношрифт)
 #[derive(Component)]
+struct NickValue; // текст набранного ника
+#[derive(Component)]
 struct ConnectButton; // прямоугольник-кнопка
 #[derive(Component)]
 struct ErrorText; // текст ошибки
@@ -37,12 +52,16 @@ pub struct MenuPlugin;
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ServerAddr>()
+            .init_resource::<Nickname>()
+            .init_resource::<MenuFocus>()
             .init_resource::<ConnectError>()
             .add_systems(OnEnter(AppState::Menu), menu_setup)
             .add_systems(
                 Update,
                 (
+                    switch_focus,         // Tab — адрес/ник
                     menu_typing,          // ввод адреса + курсор
+                    nick_typing,          // ввод ника + курсор
                     try_connect_enter,    // Enter → попытка коннекта с показом ошибки
                     click_connect_button, // клик по кнопке → то же
                     render_connect_error, // обновление текста ошибки
@@ -55,10 +74,18 @@ impl Plugin for MenuPlugin {
 
 // ===== UI =====
 
-fn menu_setup(mut commands: Commands, mut addr: ResMut<ServerAddr>, assets: Res<AssetServer>) {
+fn menu_setup(
+    mut commands: Commands,
+    mut addr: ResMut<ServerAddr>,
+    mut nick: ResMut<Nickname>,
+    assets: Res<AssetServer>,
+) {
     if addr.0.is_empty() {
         addr.0 = "127.0.0.1:6000".to_string();
     }
+    if nick.0.is_empty() {
+        nick.0 = "player".to_string();
+    }
 
     // Камера для меню
     commands.spawn((Camera2d::default(), MenuCamera));
@@ -80,7 +107,7 @@ fn menu_setup(mut commands: Commands, mut addr: ResMut<ServerAddr>, assets: Res<
             // Карточка
             root.spawn((
                 Node {
-                    width: Val::Px(560.0),
+                    width: Val::Px(760.0),
                     padding: UiRect::all(Val::Px(16.0)),
                     flex_direction: FlexDirection::Column,
                     row_gap: Val::Px(14.0),
@@ -100,45 +127,88 @@ fn menu_setup(mut commands: Commands, mut addr: ResMut<ServerAddr>, assets: Res<
                     TextColor(Color::WHITE),
                 ));
 
-                // Ряд: метка + значение (моношрифт)
-                card.spawn((
-                    Node {
-                        padding: UiRect::all(Val::Px(12.0)),
-                        flex_direction: FlexDirection::Row,
-                        align_items: AlignItems::Center,
-                        column_gap: Val::Px(8.0),
-                        ..default()
-                    },
-                    BackgroundColor(Color::srgba(0.08, 0.09, 0.12, 1.0)),
-                ))
-                .with_children(|row| {
-                    // Метка
-                    row.spawn((
-                        Text::new("Адрес:"),
-                        TextFont {
-                            font: assets.load("fonts/FiraSans-Regular.ttf"),
-                            font_size: 22.0,
+                // Ряд: адрес и ник рядом
+                card.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                })
+                .with_children(|fields| {
+                    // Адрес (метка + значение моношрифтом) — растягивается на остаток ряда
+                    fields.spawn((
+                        Node {
+                            padding: UiRect::all(Val::Px(12.0)),
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(8.0),
+                            flex_grow: 1.0,
                             ..default()
                         },
-                        TextColor(Color::srgba(0.85, 0.85, 0.9, 1.0)),
-                    ));
-                    // Значение + курсор
-                    row.spawn((
-                        Text::new(""),
-                        TextFont {
-                            font: assets.load("fonts/FiraMono-Medium.ttf"),
-                            font_size: 22.0,
+                        BackgroundColor(Color::srgba(0.08, 0.09, 0.12, 1.0)),
+                    ))
+                    .with_children(|row| {
+                        // Метка
+                        row.spawn((
+                            Text::new("Адрес:"),
+                            TextFont {
+                                font: assets.load("fonts/FiraSans-Regular.ttf"),
+                                font_size: 22.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgba(0.85, 0.85, 0.9, 1.0)),
+                        ));
+                        // Значение + курсор
+                        row.spawn((
+                            Text::new(""),
+                            TextFont {
+                                font: assets.load("fonts/FiraMono-Medium.ttf"),
+                                font_size: 22.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                            AddrValue,
+                        ));
+                    });
+
+                    // Ник — справа от адреса
+                    fields.spawn((
+                        Node {
+                            padding: UiRect::all(Val::Px(12.0)),
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(8.0),
+                            width: Val::Px(300.0),
                             ..default()
                         },
-                        TextColor(Color::WHITE),
-                        AddrValue,
-                    ));
+                        BackgroundColor(Color::srgba(0.08, 0.09, 0.12, 1.0)),
+                    ))
+                    .with_children(|row| {
+                        row.spawn((
+                            Text::new("Ник:"),
+                            TextFont {
+                                font: assets.load("fonts/FiraSans-Regular.ttf"),
+                                font_size: 22.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgba(0.85, 0.85, 0.9, 1.0)),
+                        ));
+                        row.spawn((
+                            Text::new(""),
+                            TextFont {
+                                font: assets.load("fonts/FiraMono-Medium.ttf"),
+                                font_size: 22.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                            NickValue,
+                        ));
+                    });
                 });
 
                 // Подсказка
                 card.spawn((
                     Text::new(
-                        "Введи адрес (например: 127.0.0.1:6000) и нажми Enter.\nEsc — выйти.",
+                        "Введи адрес (например: 127.0.0.1:6000) и ник, нажми Enter.\nTab — сменить поле, Esc — выйти.",
                     ),
                     TextFont {
                         font: assets.load("fonts/FiraSans-Regular.ttf"),
@@ -202,15 +272,39 @@ fn menu_cleanup(
 
 // ===== Ввод строки =====
 
+fn switch_focus(keys: Res<ButtonInput<KeyCode>>, mut focus: ResMut<MenuFocus>) {
+    if keys.just_pressed(KeyCode::Tab) {
+        *focus = match *focus {
+            MenuFocus::Addr => MenuFocus::Nick,
+            MenuFocus::Nick => MenuFocus::Addr,
+        };
+    }
+}
+
+// Блинкер курсора (только в активном поле)
+fn cursor(time: &Time, focused: bool) -> &'static str {
+    if focused && (time.elapsed_secs() * 2.0).floor() as i32 % 2 == 0 {
+        "|"
+    } else {
+        " "
+    }
+}
+
 fn menu_typing(
     mut addr: ResMut<ServerAddr>,
     mut q_value: Query<&mut Text, With<AddrValue>>,
     keys: Res<ButtonInput<KeyCode>>,
+    focus: Res<MenuFocus>,
     time: Res<Time>,
 ) {
+    if keys.just_pressed(KeyCode::Escape) {
+        std::process::exit(0);
+    }
+    let focused = *focus == MenuFocus::Addr;
+
     // Добавлялка
     let mut push_if = |kc: KeyCode, ch: char| {
-        if keys.just_pressed(kc) && addr.0.len() < 64 {
+        if focused && keys.just_pressed(kc) && addr.0.len() < 64 {
             addr.0.push(ch);
         }
     };
@@ -237,27 +331,53 @@ fn menu_typing(
 
     // '.' и ':' (через ; — часто Shift+';')
     push_if(KeyCode::Period, '.');
-    if keys.just_pressed(KeyCode::Semicolon) && addr.0.len() < 64 {
+    if focused && keys.just_pressed(KeyCode::Semicolon) && addr.0.len() < 64 {
         addr.0.push(':');
     }
 
-    // Backspace / Escape
-    if keys.just_pressed(KeyCode::Backspace) {
+    // Backspace
+    if focused && keys.just_pressed(KeyCode::Backspace) {
         addr.0.pop();
     }
-    if keys.just_pressed(KeyCode::Escape) {
-        std::process::exit(0);
+
+    if let Ok(mut t) = q_value.single_mut() {
+        *t = Text::new(format!("{}{}", addr.0, cursor(&time, focused)));
     }
+}
 
-    // Блинкер курсора
-    let cursor = if (time.elapsed_secs() * 2.0).floor() as i32 % 2 == 0 {
-        "|"
-    } else {
-        " "
-    };
+// Ник — любые печатные символы из раскладки, длину ограничивает NICKNAME_MAX_LEN
+fn nick_typing(
+    mut nick: ResMut<Nickname>,
+    mut q_value: Query<&mut Text, With<NickValue>>,
+    mut keyboard: EventReader<KeyboardInput>,
+    focus: Res<MenuFocus>,
+    time: Res<Time>,
+) {
+    let focused = *focus == MenuFocus::Nick;
+    for ev in keyboard.read() {
+        if !focused || !ev.state.is_pressed() {
+            continue;
+        }
+        match &ev.logical_key {
+            Key::Backspace => {
+                nick.0.pop();
+            }
+            Key::Character(s) => {
+                for ch in s.chars().filter(|ch| !ch.is_control()) {
+                    if nick.0.chars().count() < NICKNAME_MAX_LEN {
+                        nick.0.push(ch);
+                    }
+                }
+            }
+            Key::Space if !nick.0.is_empty() && nick.0.chars().count() < NICKNAME_MAX_LEN => {
+                nick.0.push(' ');
+            }
+            _ => {}
+        }
+    }
 
     if let Ok(mut t) = q_value.single_mut() {
-        *t = Text::new(format!("{}{}", addr.0, cursor));
+        *t = Text::new(format!("{}{}", nick.0, cursor(&time, focused)));
     }
 }
 
@@ -266,6 +386,7 @@ fn menu_typing(
 fn try_connect_enter(
     keys: Res<ButtonInput<KeyCode>>,
     addr: Res<ServerAddr>,
+    nick: Res<Nickname>,
     mut client: ResMut<QuinnetClient>,
     mut next: ResMut<NextState<AppState>>,
     mut commands: Commands,
@@ -274,6 +395,10 @@ fn try_connect_enter(
     if !keys.just_pressed(KeyCode::Enter) || addr.0.is_empty() {
         return;
     }
+    if nick.0.trim().is_empty() {
+        err.0 = Some("Введи ник".into());
+        return;
+    }
 
     match do_connect(&addr.0, &mut client, &mut commands) {
         Ok(_) => {
@@ -294,6 +419,7 @@ fn try_connect_enter(
 fn click_connect_button(
     mut q_btn: Query<&Interaction, (Changed<Interaction>, With<ConnectButton>)>,
     addr: Res<ServerAddr>,
+    nick: Res<Nickname>,
     mut client: ResMut<QuinnetClient>,
     mut next: ResMut<NextState<AppState>>,
     mut commands: Commands,
@@ -304,6 +430,10 @@ fn click_connect_button(
             if addr.0.is_empty() {
                 return;
             }
+            if nick.0.trim().is_empty() {
+                err.0 = Some("Введи ник".into());
+                return;
+            }
             match do_connect(&addr.0, &mut client, &mut commands) {
                 Ok(_) => {
                     info!("✅ connected, going Connecting");
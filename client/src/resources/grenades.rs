@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use bevy::prelude::*;
 use protocol::messages::GrenadeEvent;
+use sim::GrenadeBody;
 
 #[derive(Resource)]
 pub struct GrenadeCooldown(pub Timer);
@@ -15,13 +16,12 @@ impl Default for GrenadeCooldown {
 #[derive(Resource, Default)]
 pub struct ClientGrenades(pub HashMap<u64, GrenadeEvent>);
 
-/// Последний снапшот по гранате от сервера
+/// Предсказанная граната: та же пошаговая симуляция, что на сервере
 #[derive(Default, Clone, Copy)]
 pub struct NetState {
-    pub pos: Vec2,
-    pub vel: Vec2,
-    pub ts:  f64,
-    pub has: bool,
+    pub body: GrenadeBody,
+    pub created: f64, // время броска по часам сервера
+    pub ticks: u32,   // сколько шагов TICK_DT просчитано от created
 }
 
 /// Состояния всех гранат по их id
@@ -1,10 +1,14 @@
 use bevy::prelude::*;
 use bevy_quinnet::client::connection::ConnectionLocalId;
+use protocol::map::GameMap;
 use protocol::messages::{InputState, Stance, WorldSnapshot};
 use std::collections::{HashMap, HashSet, VecDeque};
 
 pub mod explosion_textures;
 pub mod grenades;
+pub mod rounds;
+pub mod scoreboard;
+pub mod weapons;
 
 #[derive(Resource)]
 pub struct MyPlayer {
@@ -23,6 +27,10 @@ pub struct SnapshotBuffer {
     pub delay: f64,
 }
 
+// Собранные из дельт снапшоты — база для следующих дельт; в отличие от буфера интерполяции не чистится при смерти
+#[derive(Resource, Default)]
+pub struct SnapshotBaselines(pub VecDeque<WorldSnapshot>);
+
 #[derive(Resource)]
 pub struct CurrentStance(pub Stance);
 
@@ -32,6 +40,19 @@ pub struct SendTimer(pub Timer);
 #[derive(Resource, Default)]
 pub struct SpawnedPlayers(pub HashSet<u64>);
 
+#[derive(Resource, Default)]
+pub struct PlayerNames(pub HashMap<u64, String>); // id → ник с сервера
+
+impl PlayerNames {
+    /// Ник, а пока он не пришёл — «Игрок {id}»
+    pub fn display(&self, id: u64) -> String {
+        self.0
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| format!("Игрок {id}"))
+    }
+}
+
 #[derive(Resource)]
 pub struct SeqCounter(pub u32);
 
@@ -41,6 +62,9 @@ pub struct PendingInputsClient(pub VecDeque<InputState>);
 #[derive(Resource, Default)]
 pub struct CurrentConnId(pub Option<ConnectionLocalId>);
 
+#[derive(Resource, Default)]
+pub struct NegotiatedCaps(pub u32); // capabilities из S2C::Welcome
+
 #[derive(Resource)]
 pub struct HeartbeatTimer(pub Timer);
 
@@ -81,6 +105,22 @@ pub struct UiFont(pub Handle<Font>);
 #[derive(Resource, Default)]
 pub struct HpUiMap(pub HashMap<u64, Entity>);
 
+// Броня локального игрока (источник истины — S2C::Armor)
+#[derive(Resource, Default)]
+pub struct MyArmor(pub i32);
+
+// Какие предметы карты лежат на месте (S2C::PickupStates); пока не пришло — все
+#[derive(Resource, Default)]
+pub struct PickupStates(pub Vec<bool>);
+
+// Карта, по которой строится уровень
+#[derive(Resource)]
+pub struct ActiveMap(pub GameMap);
+
+// Стены карты для предсказания движения; та же симуляция, что на сервере
+#[derive(Resource, Default)]
+pub struct MapCollision(pub sim::CollisionWorld);
+
 // Ресурс карты, заполняется на клиенте при загрузке уровня (или из сервера).
 #[derive(Resource, Default)]
 pub struct SolidTiles(pub std::collections::HashSet<IVec2>);
@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use protocol::messages::{RoundInfo, RoundPhase, Team};
+
+/// Последнее S2C::RoundState (нет в дезматче) и когда оно пришло
+#[derive(Resource, Default)]
+pub struct RoundHud {
+    pub info: Option<RoundInfo>,
+    pub received_at: f64, // time_in_seconds() клиента
+}
+
+impl RoundHud {
+    pub fn is_frozen(&self) -> bool {
+        self.info
+            .as_ref()
+            .is_some_and(|info| info.phase == RoundPhase::Freeze)
+    }
+
+    /// Остаток фазы с учётом времени, прошедшего с получения
+    pub fn time_left(&self, now: f64) -> f32 {
+        self.info.as_ref().map_or(0.0, |info| {
+            (info.time_left - (now - self.received_at) as f32).max(0.0)
+        })
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PlayerTeams(pub HashMap<u64, Team>);
@@ -0,0 +1,6 @@
+use bevy::prelude::*;
+use protocol::messages::ScoreEntry;
+
+/// Последняя таблица счёта от сервера (S2C::Scoreboard)
+#[derive(Resource, Default)]
+pub struct Scoreboard(pub Vec<ScoreEntry>);
@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+use protocol::weapons::WeaponKind;
+
+/// Патроны локального игрока (источник истины — S2C::Ammo)
+#[derive(Resource)]
+pub struct AmmoState {
+    pub weapon: WeaponKind,
+    pub mag: u32,
+    pub reserve: u32,
+    pub reloading: bool,
+}
+
+impl Default for AmmoState {
+    fn default() -> Self {
+        let weapon = WeaponKind::default();
+        AmmoState {
+            weapon,
+            mag: weapon.def().mag_size,
+            reserve: weapon.def().reserve,
+            reloading: false,
+        }
+    }
+}
+
+/// Номер последнего выстрела (сид разброса, сервер ждёт их подряд) и текущая очередь
+#[derive(Resource)]
+pub struct ShotCounter {
+    pub seq: u32,
+    pub spray: u32,
+    pub last_shot: f64, // Time::elapsed_secs_f64
+}
+
+impl Default for ShotCounter {
+    fn default() -> Self {
+        ShotCounter {
+            seq: 0,
+            spray: 0,
+            last_shot: f64::NEG_INFINITY,
+        }
+    }
+}
+
+/// Локальный темп стрельбы при зажатой ЛКМ (длительность — от активного оружия)
+#[derive(Resource)]
+pub struct FireCooldown(pub Timer);
+
+impl Default for FireCooldown {
+    fn default() -> Self {
+        FireCooldown(Timer::from_seconds(
+            WeaponKind::default().def().fire_interval as f32,
+            TimerMode::Once,
+        ))
+    }
+}
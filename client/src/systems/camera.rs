@@ -5,8 +5,7 @@ use bevy::window::PrimaryWindow;
 
 use crate::app_state::AppState;
 use crate::components::PlayerMarker;
-use crate::resources::MyPlayer;
-use crate::systems::level_fixed::{TILE, map_lines};
+use crate::resources::{ActiveMap, MyPlayer};
 
 // твои типы/функции – поправь путь, если нужны модули:
 
@@ -21,10 +20,15 @@ pub struct CameraFollowPlugin;
 impl Plugin for CameraFollowPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<CameraFollowSettings>() // ← настройки по умолчанию
-            .add_systems(OnEnter(AppState::InGame), init_level_bounds)
+            // границы — по карте от сервера, камера ждёт их появления
+            .add_systems(
+                Update,
+                init_level_bounds.run_if(resource_exists_and_changed::<ActiveMap>),
+            )
             .add_systems(
                 PostUpdate,
-                follow_player_camera_smooth.run_if(in_state(AppState::InGame)),
+                follow_player_camera_smooth
+                    .run_if(in_state(AppState::InGame).and(resource_exists::<LevelBounds>)),
             );
     }
 }
@@ -51,17 +55,8 @@ impl Default for CameraFollowSettings {
     }
 }
 
-fn init_level_bounds(mut commands: Commands) {
-    let lines = map_lines();
-    let h = lines.len() as f32;
-    let w = lines
-        .iter()
-        .map(|s| s.chars().count() as f32)
-        .max_by(|a, b| a.total_cmp(b))
-        .unwrap_or(0.0);
-
-    let size = Vec2::new(w * TILE, h * TILE);
-    let half = size * 0.5;
+fn init_level_bounds(mut commands: Commands, map: Res<ActiveMap>) {
+    let half = map.0.half_size();
 
     // было: min = (0,0), max = (w*TILE, h*TILE)
     commands.insert_resource(LevelBounds {
@@ -4,37 +4,67 @@ use protocol::constants::CH_S2C;
 use protocol::messages::S2C;
 
 use crate::app_state::AppState;
-use crate::menu::ConnectTimeout;
-use crate::resources::CurrentConnId;
+use crate::menu::{ConnectError, ConnectTimeout};
+use crate::resources::weapons::ShotCounter;
+use crate::resources::{
+    CurrentConnId, MyArmor, MyPlayer, NegotiatedCaps, PickupStates, SnapshotBaselines,
+};
 
 pub fn connecting_pump(
     mut client: ResMut<QuinnetClient>,
     conn_id: Option<Res<CurrentConnId>>,
     mut next: ResMut<NextState<AppState>>,
+    mut my: ResMut<MyPlayer>,
+    mut err: ResMut<ConnectError>,
     mut commands: Commands,
 ) {
     let Some(id) = conn_id.and_then(|c| c.0) else {
         return;
     };
-    let Some(mut conn) = client.get_connection_mut() else {
+    let Some(conn) = client.get_connection_mut() else {
         return;
     };
 
-    // Читаем только чтобы поймать первый Snapshot. Остальные сообщения можно игнорить.
+    // Ждём ответ на Hello. Снапшоты и прочее до Welcome игнорим.
+    let mut rejected = None;
     while let Some((chan, msg)) = conn.try_receive_message::<S2C>() {
         if chan != CH_S2C {
             continue;
         }
         match msg {
-            S2C::Snapshot(_snap) => {
-                // Сигнал «сервер жив» — снимаем таймер и заходим в игру.
+            S2C::Welcome {
+                protocol_version,
+                client_id,
+                capabilities,
+            } => {
+                my.id = client_id;
+                my.got = true;
+                commands.insert_resource(NegotiatedCaps(capabilities));
+                // тики нового сервера начинаются заново — старые базы не годятся
+                commands.insert_resource(SnapshotBaselines::default());
+                // номера выстрелов сервер тоже считает с нуля
+                commands.insert_resource(ShotCounter::default());
+                commands.insert_resource(MyArmor::default());
+                commands.insert_resource(PickupStates::default());
                 commands.remove_resource::<ConnectTimeout>();
-                info!("✅ Got first Snapshot, entering InGame");
+                info!("✅ Welcome (protocol v{protocol_version}, id={client_id}), entering InGame");
                 next.set(AppState::InGame);
-                // Первый снап мы не используем здесь — норм, в InGame его схватит обычная сеть.
+                // остальные сообщения заберёт обычная сеть уже в InGame
+                break;
+            }
+            S2C::Rejected { reason } => {
+                rejected = Some(reason);
                 break;
             }
             _ => { /* игнор */ }
         }
     }
+
+    if let Some(reason) = rejected {
+        warn!("⛔ Server rejected connection: {reason}");
+        let _ = client.close_connection(id);
+        err.0 = Some(format!("Сервер отклонил подключение: {reason}"));
+        commands.remove_resource::<ConnectTimeout>();
+        next.set(AppState::Menu);
+    }
 }
@@ -1,6 +1,10 @@
+use crate::menu::Nickname;
 use crate::resources::MyPlayer;
 use bevy::prelude::*;
+use bevy_quinnet::client::QuinnetClient;
 use bevy_quinnet::client::connection::ConnectionEvent;
+use protocol::constants::{CH_C2S, PROTOCOL_VERSION, SUPPORTED_CAPABILITIES};
+use protocol::messages::C2S;
 
 pub fn handle_connection_event(mut events: EventReader<ConnectionEvent>, mut me: ResMut<MyPlayer>) {
     for ConnectionEvent { client_id, .. } in events.read() {
@@ -13,3 +17,23 @@ pub fn handle_connection_event(mut events: EventReader<ConnectionEvent>, mut me:
         }
     }
 }
+
+/// Как только соединение установлено — представляемся серверу
+pub fn send_hello_on_connect(
+    mut events: EventReader<ConnectionEvent>,
+    mut client: ResMut<QuinnetClient>,
+    nick: Res<Nickname>,
+) {
+    for _ in events.read() {
+        let hello = C2S::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            client_name: concat!("cs2d-client/", env!("CARGO_PKG_VERSION")).into(),
+            capabilities: SUPPORTED_CAPABILITIES,
+            nickname: nick.0.trim().to_string(),
+        };
+        match client.connection_mut().send_message_on(CH_C2S, hello) {
+            Ok(_) => info!("👋 Sent Hello (protocol v{PROTOCOL_VERSION})"),
+            Err(e) => warn!("❌ Hello send error: {:?}", e),
+        }
+    }
+}
@@ -1,28 +1,28 @@
 use crate::{
     components::LocalPlayer,
-    resources::{MyPlayer, grenades::GrenadeCooldown},
+    resources::{grenades::GrenadeCooldown, rounds::RoundHud},
     systems::utils::time_in_seconds,
 };
 use bevy::prelude::*;
 use bevy_quinnet::client::QuinnetClient;
 use protocol::{
-    constants::{CH_C2S, GRENADE_RADIUS, GRENADE_SPEED, GRENADE_TIMER},
-    messages::{GrenadeEvent, C2S},
+    constants::{CH_C2S, GRENADE_SPEED, GRENADE_TIMER},
+    messages::{GrenadeThrow, C2S},
 };
 
 pub fn grenade_throw(
     keys: Res<ButtonInput<KeyCode>>,
-    my: Res<MyPlayer>,
     mut client: ResMut<QuinnetClient>,
     player_query: Query<&Transform, With<LocalPlayer>>,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform)>,
     mut grenade_cd: ResMut<GrenadeCooldown>,
+    round: Res<RoundHud>,
     time: Res<Time>,
 ) {
     grenade_cd.0.tick(time.delta());
 
-    if !keys.just_pressed(KeyCode::KeyG) || !grenade_cd.0.finished() {
+    if !keys.just_pressed(KeyCode::KeyG) || !grenade_cd.0.finished() || round.is_frozen() {
         return;
     }
 
@@ -58,17 +58,12 @@ pub fn grenade_throw(
     }
     dir = dir.normalize();
 
-    let ts = time_in_seconds();
-    // смещаем точку спавна от центра игрока на радиус гранаты (+1 px запас)
-    let spawn_from = player_pos + dir * (GRENADE_RADIUS + 1.0);
-
-    let ev = GrenadeEvent {
-        id: my.id ^ (ts as u64),
-        from: spawn_from,
+    // id и точку вылета назначает сервер
+    let ev = GrenadeThrow {
         dir,
         speed: GRENADE_SPEED,
         timer: GRENADE_TIMER,
-        timestamp: ts,
+        timestamp: time_in_seconds(),
     };
 
     if client
@@ -78,8 +73,8 @@ pub fn grenade_throw(
     {
         grenade_cd.0.reset();
         info!(
-            "💣 Sent ThrowGrenade {}, speed: {}, timer: {}",
-            ev.id, ev.speed, ev.timer
+            "💣 Sent ThrowGrenade, speed: {}, timer: {}",
+            ev.speed, ev.timer
         );
     }
 }
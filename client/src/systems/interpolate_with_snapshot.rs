@@ -1,20 +1,12 @@
-use crate::components::PlayerMarker;
+use crate::components::{PlayerMarker, PlayerStance};
 use crate::resources::{MyPlayer, SnapshotBuffer, TimeSync};
-use crate::systems::utils::{lerp_angle, stance_color, time_in_seconds};
+use crate::systems::utils::{lerp_angle, time_in_seconds};
 use bevy::prelude::*;
+use protocol::messages::WorldSnapshot;
 use std::collections::HashMap;
 
-pub fn interpolate_with_snapshot(
-    mut q: Query<(&mut Transform, &mut Sprite, &PlayerMarker)>,
-    buffer: Res<SnapshotBuffer>,
-    my: Res<MyPlayer>,
-    time_sync: Res<TimeSync>,
-) {
-    if buffer.snapshots.len() < 2 {
-        return;
-    }
-    let now_s = time_in_seconds() - time_sync.offset;
-    let rt = now_s - buffer.delay;
+/// Два снапшота вокруг момента `rt` (серверные секунды) и доля пути между ними
+fn bracket(buffer: &SnapshotBuffer, rt: f64) -> Option<(&WorldSnapshot, &WorldSnapshot, f32)> {
     let (mut prev, mut next) = (None, None);
     for snap in buffer.snapshots.iter() {
         if snap.server_time <= rt {
@@ -27,11 +19,41 @@ pub fn interpolate_with_snapshot(
     let (prev, next) = match (prev, next) {
         (Some(p), Some(n)) => (p, n),
         (Some(p), None) => (p, p),
-        _ => return,
+        _ => return None,
     };
     let t0 = prev.server_time;
     let t1 = next.server_time.max(t0 + 1e-4);
     let alpha = ((rt - t0) / (t1 - t0)).clamp(0.0, 1.0) as f32;
+    Some((prev, next, alpha))
+}
+
+/// Какой тик сервера сейчас на экране (целая часть и доля) — по нему сервер отматывает выстрел
+pub fn render_tick(buffer: &SnapshotBuffer, time_sync: &TimeSync) -> Option<(u32, f32)> {
+    let rt = time_in_seconds() - time_sync.offset - buffer.delay;
+    let (prev, next, alpha) = bracket(buffer, rt)?;
+    let tick = prev.tick as f64 + next.tick.wrapping_sub(prev.tick) as f64 * alpha as f64;
+    Some((tick as u32, tick.fract() as f32))
+}
+
+pub fn interpolate_with_snapshot(
+    mut q: Query<(
+        &mut Transform,
+        &mut PlayerStance,
+        &mut Visibility,
+        &PlayerMarker,
+    )>,
+    buffer: Res<SnapshotBuffer>,
+    my: Res<MyPlayer>,
+    time_sync: Res<TimeSync>,
+) {
+    if buffer.snapshots.len() < 2 {
+        return;
+    }
+    let now_s = time_in_seconds() - time_sync.offset;
+    let rt = now_s - buffer.delay;
+    let Some((prev, next, alpha)) = bracket(&buffer, rt) else {
+        return;
+    };
     let mut pmap = HashMap::new();
     for p in &prev.players {
         pmap.insert(p.id, p);
@@ -40,17 +62,30 @@ pub fn interpolate_with_snapshot(
     for p in &next.players {
         nmap.insert(p.id, p);
     }
-    for (mut t, mut s, marker) in q.iter_mut() {
+    for (mut t, mut stance, mut vis, marker) in q.iter_mut() {
         if marker.0 == my.id {
             continue;
         }
-        if let (Some(p0), Some(p1)) = (pmap.get(&marker.0), nmap.get(&marker.0)) {
-            let from = Vec2::new(p0.x, p0.y);
-            let to = Vec2::new(p1.x, p1.y);
-            t.translation = from.lerp(to, alpha).extend(0.0);
-            t.rotation = Quat::from_rotation_z(lerp_angle(p0.rotation, p1.rotation, alpha));
-            // todo need for stance texture, change here
-            // s.color = stance_color(&p1.stance);
+        match (pmap.get(&marker.0), nmap.get(&marker.0)) {
+            (Some(p0), Some(p1)) => {
+                let from = Vec2::new(p0.x, p0.y);
+                let to = Vec2::new(p1.x, p1.y);
+                t.translation = from.lerp(to, alpha).extend(0.0);
+                t.rotation = Quat::from_rotation_z(lerp_angle(p0.rotation, p1.rotation, alpha));
+                stance.set_if_neq(PlayerStance(p1.stance.clone()));
+                vis.set_if_neq(Visibility::Inherited);
+            }
+            // снова в поле зрения: интерполировать не от чего, ставим сразу
+            (None, Some(p1)) => {
+                t.translation = Vec2::new(p1.x, p1.y).extend(0.0);
+                t.rotation = Quat::from_rotation_z(p1.rotation);
+                stance.set_if_neq(PlayerStance(p1.stance.clone()));
+                vis.set_if_neq(Visibility::Inherited);
+            }
+            // сервер его не прислал — за стеной; старую позицию не показываем
+            (_, None) => {
+                vis.set_if_neq(Visibility::Hidden);
+            }
         }
     }
 }
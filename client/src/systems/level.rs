@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use protocol::constants::{LEVEL_HEIGHT, LEVEL_WIDTH, TILE_SIZE, WALL_THICKNESS};
 
-use crate::resources::SolidTiles;
+use crate::resources::{ActiveMap, SolidTiles};
 
 #[derive(Component)]
 pub struct Wall;
@@ -77,12 +77,22 @@ pub fn spawn_level_client(mut commands: Commands) {
     ));
 }
 
-pub fn fill_solid_tiles_once(
+/// Дозаполняем SolidTiles по спрайтам стен — заново при каждой смене карты.
+/// Идёт после setup_map_level, так что стены и SolidTiles уже от новой карты
+pub fn fill_solid_tiles(
     mut solids: ResMut<SolidTiles>,
+    map: Option<Res<ActiveMap>>,
     q_walls: Query<(&Transform, &Sprite), With<Wall>>,
-    mut done: Local<bool>,
+    mut pending: Local<bool>,
 ) {
-    if *done {
+    // стены появятся, когда придёт карта
+    let Some(map) = map else {
+        return;
+    };
+    if map.is_changed() {
+        *pending = true;
+    }
+    if !*pending || q_walls.is_empty() {
         return;
     }
 
@@ -110,5 +120,5 @@ pub fn fill_solid_tiles_once(
     }
 
     info!(target: "collision", "SolidTiles filled: {}", solids.0.len());
-    *done = true;
+    *pending = false;
 }
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use protocol::constants::TILE_SIZE;
+use protocol::map::Tile;
+use sim::CollisionWorld;
+use std::collections::HashSet;
+
+use crate::{
+    components::PickupMarker,
+    resources::{ActiveMap, MapCollision, SolidTiles, SpawnPoints},
+    systems::level::Wall,
+};
+
+/// Построение уровня по карте: спавнит стены, возвращает SolidTiles и SpawnPoints
+pub fn create_map_level(commands: &mut Commands, map: &ActiveMap) -> (SolidTiles, Vec<Vec2>) {
+    let mut solid: HashSet<IVec2> = HashSet::new();
+    let mut spawns: Vec<Vec2> = Vec::new();
+
+    for (x, y, tile) in map.0.tiles() {
+        let world_xy = map.0.tile_center(x, y);
+
+        match tile {
+            Tile::Wall => {
+                solid.insert(IVec2::new(x as i32, y as i32));
+            }
+            Tile::Spawn | Tile::SpawnT | Tile::SpawnCt => {
+                spawns.push(world_xy);
+            }
+            Tile::Empty | Tile::HealthPack | Tile::ArmorVest => {}
+        }
+    }
+
+    // стены рисуем слитыми прямоугольниками: спрайт на блок, а не на клетку
+    for r in map.0.wall_rects() {
+        commands.spawn((
+            Sprite {
+                color: Color::srgba(0.25, 0.28, 0.34, 1.0),
+                custom_size: Some(r.size()),
+                ..default()
+            },
+            Transform::from_translation(map.0.rect_center(&r).extend(0.0)),
+            GlobalTransform::default(),
+            Wall, // твой маркер стены
+        ));
+    }
+
+    // предметы: индекс совпадает с номером в S2C::PickupStates
+    for (i, (tile, pos)) in map.0.pickups().into_iter().enumerate() {
+        let color = match tile {
+            Tile::HealthPack => Color::srgb(0.9, 0.2, 0.2),
+            _ => Color::srgb(0.3, 0.5, 1.0),
+        };
+        commands.spawn((
+            Sprite {
+                color,
+                custom_size: Some(Vec2::splat(TILE_SIZE * 0.5)),
+                ..default()
+            },
+            Transform::from_translation(pos.extend(0.1)),
+            Visibility::default(),
+            PickupMarker(i),
+        ));
+    }
+
+    (SolidTiles(solid), spawns)
+}
+
+/// Строим уровень, когда сервер сообщил карту (и заново, если она сменилась)
+pub fn setup_map_level(
+    mut commands: Commands,
+    map: Res<ActiveMap>,
+    q_old: Query<Entity, Or<(With<Wall>, With<PickupMarker>)>>,
+) {
+    for e in &q_old {
+        commands.entity(e).despawn();
+    }
+    let (solid, spawns) = create_map_level(&mut commands, &map);
+
+    commands.insert_resource(solid);
+    commands.insert_resource(SpawnPoints(spawns));
+    commands.insert_resource(MapCollision(CollisionWorld::from_map(&map.0)));
+}
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use protocol::map::{GameMap, map_path, maps_dir};
+use std::path::PathBuf;
+
+/// Имя карты от сервера идёт в путь к файлу — пускаем только простые имена
+fn is_safe_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+}
+
+/// Куда складываем скачанные с серверов карты
+fn cache_path(name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join("cs2d-maps")
+        .join(format!("{name}.txt"))
+}
+
+/// Локальная копия карты с тем же хэшем: сначала assets/maps, потом кэш скачанных
+pub fn find_local_map(name: &str, hash: u64) -> Option<GameMap> {
+    if !is_safe_name(name) {
+        return None;
+    }
+    [map_path(&maps_dir(), name), cache_path(name)]
+        .iter()
+        .filter_map(|path| GameMap::load(path).ok())
+        .find(|map| map.hash() == hash)
+}
+
+/// Сохраняем скачанную карту, чтобы в следующий раз не качать
+pub fn store_downloaded_map(map: &GameMap) {
+    if !is_safe_name(&map.name) {
+        return;
+    }
+    let path = cache_path(&map.name);
+    let saved = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, map.to_text()));
+    if let Err(e) = saved {
+        warn!("⚠ Не удалось сохранить карту в {}: {e}", path.display());
+    }
+}
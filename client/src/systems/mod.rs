@@ -20,6 +20,9 @@ pub mod corpse_lc;
 pub mod connecting_pump;
 pub mod ensure_my_id;
 pub mod sync_local;
-pub mod level_fixed;
+pub mod level_map;
+pub mod map_sync;
 pub mod camera;
-pub mod aim;
\ No newline at end of file
+pub mod aim;
+pub mod pickups;
+pub mod stance_visuals;
\ No newline at end of file
@@ -1,34 +1,49 @@
 use std::str::FromStr;
 
 use crate::app_state::AppState;
-use crate::components::{Corpse, GrenadeNet, LocalPlayer, PlayerMarker};
-use crate::constants::{BULLET_SPEED, BULLET_TTL};
+use crate::components::{
+    Corpse, GrenadeNet, LocalPlayer, PlayerMarker, PlayerStance, StanceOutline,
+};
+use crate::constants::{BULLET_SPEED, MAX_GRENADE_CATCHUP, STANCE_OUTLINE};
 use crate::events::{
     GrenadeDetonatedEvent, GrenadeSpawnEvent, PlayerDamagedEvent, PlayerDied, PlayerLeftEvent,
 };
 use crate::menu::ConnectTimeout;
 use crate::resources::grenades::{GrenadeStates, NetState};
+use crate::resources::rounds::{PlayerTeams, RoundHud};
+use crate::resources::scoreboard::Scoreboard;
+use crate::resources::weapons::AmmoState;
 use crate::resources::{
-    ClientLatency, DeadPlayers, HpUiMap, LastKnownPos, MyPlayer, PendingInputsClient,
-    SnapshotBuffer, SpawnedPlayers, TimeSync, UiFont, WallAabbCache,
+    ActiveMap, ClientLatency, DeadPlayers, HpUiMap, LastKnownPos, MapCollision, MyArmor, MyPlayer,
+    PendingInputsClient, PickupStates, PlayerNames, SnapshotBaselines, SnapshotBuffer,
+    SpawnedPlayers, TimeSync, UiFont, WallAabbCache,
 };
+use crate::systems::map_sync::{find_local_map, store_downloaded_map};
 use crate::systems::shoot::spawn_tracer;
-use crate::systems::utils::{raycast_to_walls_cached, spawn_hp_ui, time_in_seconds};
+use crate::systems::utils::{
+    raycast_to_walls_cached, spawn_hp_ui, stance_outline, time_in_seconds,
+};
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_quinnet::client::QuinnetClient;
-use protocol::constants::{CH_S2C, MOVE_SPEED, PLAYER_SIZE, TICK_DT};
-use protocol::messages::{InputState, S2C};
+use protocol::constants::{CH_C2S, CH_S2C, CH_S2C_UNRELIABLE, PLAYER_SIZE, TICK_DT};
+use protocol::delta::apply_delta;
+use protocol::map::GameMap;
+use protocol::messages::{C2S, S2C, Stance};
+use protocol::weapons::pellet_dirs;
+use sim::{GrenadeBody, MoveState, advance_grenade, step_player};
 
 #[derive(SystemParam)]
 pub struct NetCtx<'w, 's> {
     pub commands: Commands<'w, 's>,
 
     pub buffer: ResMut<'w, SnapshotBuffer>,
+    pub baselines: ResMut<'w, SnapshotBaselines>,
     pub time_sync: ResMut<'w, TimeSync>,
     pub my: ResMut<'w, MyPlayer>,
     pub pending: ResMut<'w, PendingInputsClient>,
     pub spawned: ResMut<'w, SpawnedPlayers>,
+    pub names: ResMut<'w, PlayerNames>,
     pub dead: ResMut<'w, DeadPlayers>,
     pub latency: ResMut<'w, ClientLatency>,
     pub hp_ui_map: ResMut<'w, HpUiMap>,
@@ -47,7 +62,15 @@ pub struct NetCtx<'w, 's> {
 
     // прочее
     pub grenade_states: ResMut<'w, GrenadeStates>,
+    pub ammo: ResMut<'w, AmmoState>,
+    pub armor: ResMut<'w, MyArmor>,
+    pub pickups: ResMut<'w, PickupStates>,
+    pub round: ResMut<'w, RoundHud>,
+    pub teams: ResMut<'w, PlayerTeams>,
+    pub scoreboard: ResMut<'w, Scoreboard>,
     pub wall_cache: Res<'w, WallAabbCache>,
+    pub collision: Res<'w, MapCollision>,
+    pub map: Option<Res<'w, ActiveMap>>,
     pub last_pos: Option<ResMut<'w, LastKnownPos>>,
     pub app_state: Res<'w, State<AppState>>,
     pub next_state: ResMut<'w, NextState<AppState>>,
@@ -60,14 +83,50 @@ pub fn receive_server_messages(mut client: ResMut<QuinnetClient>, mut net: NetCt
     };
 
     while let Some((chan, msg)) = conn.try_receive_message::<S2C>() {
-        if chan != CH_S2C {
+        if chan != CH_S2C && chan != CH_S2C_UNRELIABLE {
             continue;
         }
         match msg {
+            // рукопожатие обрабатывает connecting_pump
+            S2C::Welcome { .. } | S2C::Rejected { .. } => {}
+
             // ===================================================
             // 1) СНАПШОТ
             // ===================================================
-            S2C::Snapshot(snap) => {
+            S2C::Snapshot(packed) => {
+                // квантование идёт от границ карты: пока её нет, снапшоты не подтверждаем
+                let Some(map) = net.map.as_deref() else {
+                    continue;
+                };
+                let Some(delta) = packed.unpack(map.0.half_size()) else {
+                    warn!("[Network] битый снапшот — пропускаем");
+                    continue;
+                };
+                // канал без порядка: устаревшие (уже есть снапшот новее) пропускаем
+                if net.baselines.0.back().is_some_and(|b| b.tick >= delta.tick) {
+                    continue;
+                }
+                let baseline = match delta.baseline {
+                    Some(base) => match net.baselines.0.iter().find(|b| b.tick == base) {
+                        Some(b) => Some(b),
+                        None => {
+                            // базы нет — не подтверждаем, сервер пришлёт полный снапшот
+                            debug!("[Network] нет базы {base} для снапшота {}", delta.tick);
+                            continue;
+                        }
+                    },
+                    None => None,
+                };
+                let Some(snap) = apply_delta(baseline, delta) else {
+                    continue;
+                };
+                conn.send_message_on(CH_C2S, C2S::SnapshotAck { tick: snap.tick })
+                    .ok();
+                net.baselines.0.push_back(snap.clone());
+                while net.baselines.0.len() > 120 {
+                    net.baselines.0.pop_front();
+                }
+
                 let now_client = time_in_seconds();
 
                 // time sync один раз на пустом буфере
@@ -78,21 +137,24 @@ pub fn receive_server_messages(mut client: ResMut<QuinnetClient>, mut net: NetCt
 
                 // reconciliation локального игрока
                 if let Ok(mut t) = net.q_local.single_mut() {
-                    if let Some(ack) = snap.last_input_seq.get(&net.my.id) {
-                        if let Some(ps) = snap.players.iter().find(|p| p.id == net.my.id) {
-                            t.translation = Vec3::new(ps.x, ps.y, t.translation.z);
-                            t.rotation = Quat::from_rotation_z(ps.rotation);
-                            while let Some(front) = net.pending.0.front() {
-                                if front.seq <= *ack {
-                                    net.pending.0.pop_front();
-                                } else {
-                                    break;
-                                }
-                            }
-                            for inp in net.pending.0.iter() {
-                                simulate_input(&mut *t, inp);
+                    if let Some(ps) = snap.players.iter().find(|p| p.id == net.my.id) {
+                        while let Some(front) = net.pending.0.front() {
+                            if front.seq <= snap.ack_seq {
+                                net.pending.0.pop_front();
+                            } else {
+                                break;
                             }
                         }
+                        // от серверной позиции переигрываем неподтверждённые инпуты тем же шагом
+                        let mut state = MoveState {
+                            pos: Vec2::new(ps.x, ps.y),
+                            rot: ps.rotation,
+                        };
+                        for inp in net.pending.0.iter() {
+                            state = step_player(state, inp, &net.collision.0);
+                        }
+                        t.translation = state.pos.extend(t.translation.z);
+                        t.rotation = Quat::from_rotation_z(state.rot);
                     }
                 }
 
@@ -106,7 +168,17 @@ pub fn receive_server_messages(mut client: ResMut<QuinnetClient>, mut net: NetCt
 
                     if net.spawned.0.insert(p.id) {
                         let label = String::from_str("snapshot").unwrap();
-                        spawn_player(&mut net.commands, &net.my, id, p.x, p.y, p.rotation, label);
+                        let nickname = net.names.display(id);
+                        let pos = Vec2::new(p.x, p.y);
+                        spawn_player(
+                            &mut net.commands,
+                            &net.my,
+                            id,
+                            &nickname,
+                            pos,
+                            p.rotation,
+                            label,
+                        );
                     }
 
                     if !net.hp_ui_map.0.contains_key(&p.id) {
@@ -139,41 +211,42 @@ pub fn receive_server_messages(mut client: ResMut<QuinnetClient>, mut net: NetCt
             S2C::ShootFx(fx) => {
                 info!("💥 [Client] got FX from {} at {:?}", fx.shooter_id, fx.from);
 
-                let max_dist = BULLET_SPEED * BULLET_TTL;
-                let dir = fx.dir.normalize_or_zero();
-                let hit_dist = raycast_to_walls_cached(fx.from, dir, max_dist, &net.wall_cache.0);
+                // трассер на каждую дробину, не дальше дальности оружия
+                let def = fx.weapon.def();
+                let max_dist = def.range;
+                for dir in pellet_dirs(fx.dir, def, &fx.shot) {
+                    let hit_dist =
+                        raycast_to_walls_cached(fx.from, dir, max_dist, &net.wall_cache.0);
 
-                if hit_dist > 0.5 {
-                    let ttl = hit_dist / BULLET_SPEED;
-                    spawn_tracer(&mut net.commands, fx.from, dir, ttl);
+                    if hit_dist > 0.5 {
+                        let ttl = hit_dist / BULLET_SPEED;
+                        spawn_tracer(&mut net.commands, fx.from, dir, ttl);
+                    }
                 }
             }
 
             // ===================================================
             // 3) СПАВН / РЕСПАВН
             // ===================================================
-            S2C::PlayerConnected { id, x, y } | S2C::PlayerRespawn { id, x, y } => {
-                net.dead.0.remove(&id);
-                net.buffer.snapshots.clear();
-
-                if net.spawned.0.remove(&id) {
-                    for (ent, marker) in net.q_marker.iter() {
-                        if marker.0 == id {
-                            net.commands.entity(ent).despawn();
-                            break;
-                        }
-                    }
-                }
-
-                let rotation = 0.0;
-                let label = String::from_str("new/respawn").unwrap();
-                spawn_player(&mut net.commands, &net.my, id, x, y, rotation, label);
-                net.spawned.0.insert(id);
-
-                if let Some(last_pos) = net.last_pos.as_deref_mut() {
-                    last_pos.0.insert(id, (Vec2::new(x, y), rotation));
+            S2C::PlayerConnected {
+                id,
+                x,
+                y,
+                nickname,
+                spawned,
+            } => {
+                info!("🙋 Игрок {id} «{nickname}» вошёл");
+                net.names.0.insert(id, nickname);
+                if spawned {
+                    respawn_player(&mut net, id, x, y);
                 }
             }
+            S2C::PlayerRespawn { id, x, y } => {
+                respawn_player(&mut net, id, x, y);
+            }
+            S2C::PlayerName { id, nickname } => {
+                net.names.0.insert(id, nickname);
+            }
 
             // ===================================================
             // 4) ИГРОК ВЫШЕЛ
@@ -189,6 +262,8 @@ pub fn receive_server_messages(mut client: ResMut<QuinnetClient>, mut net: NetCt
                     info!("🔌 PlayerLeft: игрок {} вышел — despawn", left_id);
                 }
 
+                net.teams.0.remove(&left_id);
+                net.names.0.remove(&left_id);
                 net.ev_left.write(PlayerLeftEvent(left_id));
             }
 
@@ -202,6 +277,8 @@ pub fn receive_server_messages(mut client: ResMut<QuinnetClient>, mut net: NetCt
                     info!("🔌 PlayerLeft: игрок {} вышел — despawn", id);
                 }
 
+                net.teams.0.remove(&id);
+                net.names.0.remove(&id);
                 net.ev_left.write(PlayerLeftEvent(id));
             }
 
@@ -217,6 +294,9 @@ pub fn receive_server_messages(mut client: ResMut<QuinnetClient>, mut net: NetCt
                 let one_way = (rtt - (now - server_time)) * 0.5;
                 net.latency.rtt = rtt;
                 net.latency.offset = server_time - (client_time + one_way);
+                // возвращаем серверную метку — сервер сам меряет наш пинг для таблицы
+                conn.send_message_on(CH_C2S, C2S::PingEcho { server_time })
+                    .ok();
             }
 
             // ===================================================
@@ -232,30 +312,78 @@ pub fn receive_server_messages(mut client: ResMut<QuinnetClient>, mut net: NetCt
             // ===================================================
             S2C::GrenadeSpawn(ev) => {
                 let printable_ev = ev.clone();
+                // полёт дальше считаем сами; timestamp — время броска на сервере
+                net.grenade_states.0.insert(
+                    ev.id,
+                    NetState {
+                        body: GrenadeBody {
+                            pos: ev.from,
+                            vel: ev.dir * ev.speed,
+                        },
+                        created: ev.timestamp,
+                        ticks: 0,
+                    },
+                );
                 net.ev_grenade_spawn.write(GrenadeSpawnEvent(ev));
                 info!("💣 GrenadeSpawn {}", printable_ev.id);
             }
 
             S2C::GrenadeSync { id, pos, vel, ts } => {
-                // info!("grenades sync: {:?}", pos);
-                let e = net.grenade_states.0.entry(id).or_default();
-                *e = NetState {
-                    pos,
-                    vel,
-                    ts,
-                    has: true,
-                };
+                // сверка: откатываемся к серверному тику, apply_grenade_net догонит до «сейчас»;
+                // канал ненадёжный, но опоздавшая сверка безвредна — шаг детерминирован
+                if let Some(st) = net.grenade_states.0.get_mut(&id) {
+                    let tick = ((ts - st.created) / TICK_DT as f64).round().max(0.0) as u32;
+                    st.body = GrenadeBody { pos, vel };
+                    st.ticks = tick;
+                }
             }
 
             S2C::GrenadeDetonated { id, pos } => {
+                net.grenade_states.0.remove(&id);
                 net.ev_grenade_detonated
                     .write(GrenadeDetonatedEvent { id, pos });
             }
 
             // ===================================================
-            // 8) СМЕРТЬ
+            // 8) ПАТРОНЫ
+            // ===================================================
+            S2C::Ammo {
+                weapon,
+                mag,
+                reserve,
+                reloading,
+            } => {
+                net.ammo.weapon = weapon;
+                net.ammo.mag = mag;
+                net.ammo.reserve = reserve;
+                net.ammo.reloading = reloading;
+            }
+
+            // ===================================================
+            // 9) РАУНДЫ
             // ===================================================
-            S2C::PlayerDied { victim, killer } => {
+            S2C::RoundState(info) => {
+                info!("🏁 Раунд {}: {:?}", info.round, info.phase);
+                net.round.info = Some(info);
+                net.round.received_at = time_in_seconds();
+            }
+
+            S2C::PlayerTeam { id, team } => {
+                net.teams.0.insert(id, team);
+            }
+
+            S2C::Scoreboard(entries) => {
+                net.scoreboard.0 = entries;
+            }
+
+            // ===================================================
+            // 10) СМЕРТЬ
+            // ===================================================
+            S2C::PlayerDied {
+                victim,
+                killer,
+                cause,
+            } => {
                 info!("[Client]   PlayerDied victim={}", victim);
 
                 if let Some(last_pos) = net.last_pos.as_ref() {
@@ -279,6 +407,7 @@ pub fn receive_server_messages(mut client: ResMut<QuinnetClient>, mut net: NetCt
                 net.dead.0.insert(victim);
 
                 if victim == net.my.id {
+                    net.armor.0 = 0;
                     for (ent, _) in net.q_marker.iter().filter(|(_, m)| m.0 == victim) {
                         net.commands.entity(ent).despawn();
                         net.spawned.0.remove(&victim);
@@ -289,30 +418,93 @@ pub fn receive_server_messages(mut client: ResMut<QuinnetClient>, mut net: NetCt
                     net.spawned.0.remove(&victim);
                 }
 
-                net.ev_died.write(PlayerDied { victim, killer });
+                net.ev_died.write(PlayerDied {
+                    victim,
+                    killer,
+                    cause,
+                });
                 info!("💀 Игрок {} погиб ({:?})", victim, killer);
             }
+
+            // ===================================================
+            // 11) КАРТА
+            // ===================================================
+            S2C::MapInfo { name, hash } => {
+                if let Some(map) = find_local_map(&name, hash) {
+                    info!("🗺 Карта «{name}» есть локально");
+                    net.commands.insert_resource(ActiveMap(map));
+                } else {
+                    info!("🗺 Карты «{name}» нет или хэш {hash:#x} не совпал — скачиваем");
+                    conn.send_message_on(CH_C2S, C2S::RequestMap).ok();
+                }
+            }
+
+            S2C::MapData { name, data } => match GameMap::parse(&name, &data) {
+                Ok(map) => {
+                    info!("🗺 Карта «{name}» получена ({}x{})", map.width, map.height);
+                    store_downloaded_map(&map);
+                    net.commands.insert_resource(ActiveMap(map));
+                }
+                Err(e) => error!("❌ Карта «{name}» от сервера не разобралась: {e}"),
+            },
+
+            // ===================================================
+            // 12) ПРЕДМЕТЫ И БРОНЯ
+            // ===================================================
+            S2C::Armor(armor) => {
+                net.armor.0 = armor;
+            }
+
+            S2C::PlayerHealed { id, new_hp } => {
+                // тот же путь, что и урон: HP-бар обновится, попап с нулём не рисуется
+                net.ev_damage.write(PlayerDamagedEvent {
+                    id,
+                    new_hp,
+                    damage: 0,
+                });
+            }
+
+            S2C::PickupStates(states) => {
+                net.pickups.0 = states;
+            }
         }
     }
 }
 
-fn simulate_input(t: &mut Transform, inp: &InputState) {
-    let mut dir = Vec2::ZERO;
-    if inp.up {
-        dir.y += 1.0;
+/// Игрок (пере)появился в точке: убираем старую сущность, если была, и спавним заново
+fn respawn_player(net: &mut NetCtx, id: u64, x: f32, y: f32) {
+    net.dead.0.remove(&id);
+    net.buffer.snapshots.clear();
+    if id == net.my.id {
+        net.armor.0 = 0;
     }
-    if inp.down {
-        dir.y -= 1.0;
-    }
-    if inp.left {
-        dir.x -= 1.0;
+
+    if net.spawned.0.remove(&id) {
+        for (ent, marker) in net.q_marker.iter() {
+            if marker.0 == id {
+                net.commands.entity(ent).despawn();
+                break;
+            }
+        }
     }
-    if inp.right {
-        dir.x += 1.0;
+
+    let rotation = 0.0;
+    let label = String::from_str("new/respawn").unwrap();
+    let nickname = net.names.display(id);
+    spawn_player(
+        &mut net.commands,
+        &net.my,
+        id,
+        &nickname,
+        Vec2::new(x, y),
+        rotation,
+        label,
+    );
+    net.spawned.0.insert(id);
+
+    if let Some(last_pos) = net.last_pos.as_deref_mut() {
+        last_pos.0.insert(id, (Vec2::new(x, y), rotation));
     }
-    dir = dir.normalize_or_zero();
-    t.translation += (dir * MOVE_SPEED * TICK_DT).extend(0.0);
-    t.rotation = Quat::from_rotation_z(inp.rotation);
 }
 
 // Утилита для единообразного создания сущности игрока.
@@ -320,12 +512,12 @@ fn spawn_player(
     commands: &mut Commands,
     me: &ResMut<MyPlayer>,
     id: u64,
-    x: f32,
-    y: f32,
+    nickname: &str,
+    pos: Vec2,
     rot: f32,
     from: String,
 ) -> Entity {
-    let tf = Transform::from_xyz(x, y, 0.0).with_rotation(Quat::from_rotation_z(rot));
+    let tf = Transform::from_xyz(pos.x, pos.y, 0.0).with_rotation(Quat::from_rotation_z(rot));
     let is_local = id == me.id;
 
     let entity = commands
@@ -342,12 +534,26 @@ fn spawn_player(
             tf,
             GlobalTransform::default(),
             PlayerMarker(id),
+            PlayerStance(Stance::Standing),
             Name::new(format!(
-                "Player[{}] {}",
+                "Player[{}] {} «{}»",
                 if is_local { "LOCAL" } else { "REMOTE" },
-                id
+                id,
+                nickname
             )),
         ))
+        .with_children(|parent| {
+            // обводка чуть ниже спрайта; размер и цвет дальше ведёт apply_stance_visuals
+            parent.spawn((
+                Sprite {
+                    color: stance_outline(&Stance::Standing),
+                    custom_size: Some(Vec2::splat(PLAYER_SIZE + STANCE_OUTLINE * 2.0)),
+                    ..default()
+                },
+                Transform::from_xyz(0.0, 0.0, -0.05),
+                StanceOutline,
+            ));
+        })
         .id();
 
     if is_local {
@@ -356,37 +562,40 @@ fn spawn_player(
         // Компонент для плагина камеры (только локальному), только если
         // надо ехать за игроком
         // commands.entity(entity).insert(Velocity(Vec2::ZERO));
-        info!("[Client]{from} spawn LOCAL {}", id);
+        info!("[Client]{from} spawn LOCAL {} «{}»", id, nickname);
     } else {
-        info!("[Client][{from}] spawn REMOTE {}", id);
+        info!("[Client][{from}] spawn REMOTE {} «{}»", id, nickname);
     }
 
     entity
 }
 
 /// Применяем сетевое состояние к Transform гранат.
-/// Между снапшотами — лёгкая экстраполяция pos += vel * (now - ts)
+/// Между сверками GrenadeSync гоним общий с сервером фиксированный шаг `sim::advance_grenade`
+/// до текущего серверного тика (не больше MAX_GRENADE_CATCHUP тиков за кадр)
 pub fn apply_grenade_net(
-    states: Res<GrenadeStates>,
+    mut states: ResMut<GrenadeStates>,
     time_sync: Res<TimeSync>,
+    collision: Res<MapCollision>,
     mut q: Query<(&GrenadeNet, &mut Transform)>,
 ) {
+    if !time_sync.offset.is_finite() {
+        return;
+    }
     let now_server = time_in_seconds() - time_sync.offset; // серверные секунды
-    for (net, mut tf) in q.iter_mut() {
-        // info!("apply id={} pos={:?}", net.id, tf.translation.truncate());
 
-        if let Some(s) = states.0.get(&net.id) {
-            if !s.has {
-                continue;
-            }
-            let mut dt = (now_server - s.ts) as f32;
-            if !time_sync.offset.is_finite() {
-                dt = 0.0;
-            }
-            dt = dt.clamp(0.0, 0.25);
-            let pos = s.pos + s.vel * dt;
-            tf.translation.x = pos.x;
-            tf.translation.y = pos.y;
+    // догоняем каждую гранату до текущего тика той же симуляцией, что на сервере
+    for st in states.0.values_mut() {
+        let due = ((now_server - st.created) / TICK_DT as f64).max(0.0) as u32;
+        let due = due.min(st.ticks + MAX_GRENADE_CATCHUP);
+        st.body = advance_grenade(st.body, due.saturating_sub(st.ticks), &collision.0);
+        st.ticks = st.ticks.max(due);
+    }
+
+    for (net, mut tf) in q.iter_mut() {
+        if let Some(st) = states.0.get(&net.id) {
+            tf.translation.x = st.body.pos.x;
+            tf.translation.y = st.body.pos.y;
         }
     }
 }
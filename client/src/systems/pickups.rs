@@ -0,0 +1,18 @@
+use crate::components::PickupMarker;
+use crate::resources::PickupStates;
+use bevy::prelude::*;
+
+/// Подобранные предметы прячем, вернувшиеся показываем
+pub fn sync_pickup_visibility(
+    states: Res<PickupStates>,
+    mut q: Query<(&PickupMarker, &mut Visibility)>,
+) {
+    for (marker, mut vis) in &mut q {
+        let available = states.0.get(marker.0).copied().unwrap_or(true);
+        vis.set_if_neq(if available {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
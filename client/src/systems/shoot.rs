@@ -2,25 +2,58 @@ use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_quinnet::client::QuinnetClient;
 use crate::components::{LocalPlayer, Bullet};
-use crate::constants::{BULLET_SPEED, BULLET_TTL};
-use crate::resources::{MyPlayer};
-use crate::systems::utils::time_in_seconds;
+use crate::constants::BULLET_SPEED;
+use crate::resources::rounds::RoundHud;
+use crate::resources::weapons::{AmmoState, FireCooldown, ShotCounter};
+use crate::resources::{SnapshotBuffer, TimeSync};
+use crate::systems::interpolate_with_snapshot::render_tick;
 use protocol::messages::{ShootEvent, C2S};
-use protocol::constants::{CH_C2S};
+use protocol::constants::CH_C2S;
+use protocol::weapons::{WeaponKind, next_spray};
+use std::time::Duration;
 
 pub fn shoot_mouse(
     buttons: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window, With<PrimaryWindow>>,
     cam_q: Query<(&Camera, &GlobalTransform)>,
     player_q: Query<&Transform, With<LocalPlayer>>,
-    my: Res<MyPlayer>,
     mut client: ResMut<QuinnetClient>,
     mut commands: Commands,
+    mut fire_cd: ResMut<FireCooldown>,
+    mut ammo: ResMut<AmmoState>,
+    mut shots: ResMut<ShotCounter>,
+    keys: Res<ButtonInput<KeyCode>>,
+    round: Res<RoundHud>,
+    time: Res<Time>,
+    buffer: Res<SnapshotBuffer>,
+    time_sync: Res<TimeSync>,
 ) {
-    if !buttons.just_pressed(MouseButton::Left) {
+    let interval = Duration::from_secs_f64(ammo.weapon.def().fire_interval);
+    if fire_cd.0.duration() != interval {
+        fire_cd.0.set_duration(interval);
+    }
+    fire_cd.0.tick(time.delta());
+
+    // автоматический огонь: стреляем, пока зажата ЛКМ
+    if !buttons.pressed(MouseButton::Left) || !fire_cd.0.finished() {
         return;
     }
-    println!("🖱 [Client] Mouse Left pressed");
+    if ammo.reloading || round.is_frozen() {
+        return;
+    }
+    if ammo.mag == 0 {
+        // пустой магазин — просим перезарядку
+        if ammo.reserve > 0
+            && client
+                .connection_mut()
+                .send_message_on(CH_C2S, C2S::Reload)
+                .is_ok()
+        {
+            ammo.reloading = true;
+        }
+        return;
+    }
+    debug!("🖱 [Client] Mouse Left pressed");
 
     let window = match windows.single() {
         Ok(w) => w,
@@ -59,23 +92,98 @@ pub fn shoot_mouse(
     };
     let dir = (world - player_pos).normalize_or_zero();
 
+    // сервер проверит попадание по тому тику, что был у нас на экране
+    let Some((tick, tick_frac)) = render_tick(&buffer, &time_sync) else {
+        return;
+    };
+    // разброс: сид — номер выстрела, очередь и ходьба расширяют конус
+    let now = time.elapsed_secs_f64();
+    let spray = next_spray(shots.spray, now - shots.last_shot, ammo.weapon.def());
+    let moving = [KeyCode::KeyW, KeyCode::KeyA, KeyCode::KeyS, KeyCode::KeyD]
+        .iter()
+        .any(|&key| keys.pressed(key));
     let shoot = ShootEvent {
-        shooter_id: my.id,
         dir,
-        timestamp: time_in_seconds(),
+        tick,
+        tick_frac,
+        seq: shots.seq.wrapping_add(1),
+        spray,
+        moving,
     };
     match client
         .connection_mut()
         .send_message_on(CH_C2S, C2S::Shoot(shoot.clone()))
     {
-        Ok(_) => println!("📤 [Client] Sent ShootEvent: {:?}", shoot),
+        Ok(_) => {
+            // локально списываем патрон, сервер пришлёт точное значение
+            fire_cd.0.reset();
+            ammo.mag -= 1;
+            shots.seq = shoot.seq;
+            shots.spray = spray;
+            shots.last_shot = now;
+            debug!("📤 [Client] Sent ShootEvent: {:?}", shoot)
+        }
         Err(e) => println!("❌ [Client] Shoot send error: {:?}", e),
     };
-    println!("🎨 [Client] Local spawn_tracer");
+    debug!("🎨 [Client] Local spawn_tracer");
     // трассер рисуется по ивенту, тут не нужен
     // spawn_tracer(&mut commands, player_pos, dir);
 }
 
+pub fn reload_weapon(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut client: ResMut<QuinnetClient>,
+    mut ammo: ResMut<AmmoState>,
+) {
+    if !keys.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+    if ammo.reloading || ammo.reserve == 0 || ammo.mag >= ammo.weapon.def().mag_size {
+        return;
+    }
+    if client
+        .connection_mut()
+        .send_message_on(CH_C2S, C2S::Reload)
+        .is_ok()
+    {
+        ammo.reloading = true;
+        info!("🔄 Sent Reload");
+    }
+}
+
+/// Клавиши 1–4 — слоты оружия (порядок WeaponKind::ALL)
+pub fn switch_weapon(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut client: ResMut<QuinnetClient>,
+    ammo: Res<AmmoState>,
+) {
+    const SLOT_KEYS: [KeyCode; 4] = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+    ];
+
+    let Some(kind) = SLOT_KEYS
+        .iter()
+        .zip(WeaponKind::ALL)
+        .find_map(|(key, kind)| keys.just_pressed(*key).then_some(kind))
+    else {
+        return;
+    };
+    if kind == ammo.weapon {
+        return;
+    }
+    // активное оружие поменяется по ответу S2C::Ammo
+    if client
+        .connection_mut()
+        .send_message_on(CH_C2S, C2S::SwitchWeapon(kind))
+        .is_ok()
+    {
+        info!("🔫 Sent SwitchWeapon {:?}", kind);
+    }
+}
+
 pub fn spawn_tracer(commands: &mut Commands, from: Vec2, dir: Vec2, ttl:f32) {
     commands.spawn((
         Sprite {
@@ -10,6 +10,10 @@ pub fn spawn_damage_popups(
     asset_server: Res<AssetServer>,
 ) {
     for ev in reader.read() {
+        // лечение (аптечка) приходит с damage = 0 — попап не нужен
+        if ev.damage == 0 {
+            continue;
+        }
         if let Some((_, tf)) = query.iter().find(|(m, _)| m.0 == ev.id) {
             let font = asset_server.load("fonts/FiraSans-Bold.ttf");
 
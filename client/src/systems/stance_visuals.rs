@@ -0,0 +1,36 @@
+use crate::components::{LocalPlayer, PlayerStance, StanceOutline};
+use crate::constants::STANCE_OUTLINE;
+use crate::resources::CurrentStance;
+use crate::systems::utils::{stance_color, stance_outline, stance_size};
+use bevy::prelude::*;
+
+/// Размер, цвет и обводка спрайта по стойке — один путь для своего и чужих игроков.
+/// После респавна спрайт новый, поэтому сверяем каждый кадр, а пишем только при разнице
+pub fn apply_stance_visuals(
+    current: Res<CurrentStance>,
+    mut q_players: Query<
+        (&PlayerStance, Has<LocalPlayer>, &mut Sprite, &Children),
+        Without<StanceOutline>,
+    >,
+    mut q_outline: Query<&mut Sprite, With<StanceOutline>>,
+) {
+    for (remote, is_local, mut sprite, children) in q_players.iter_mut() {
+        let stance = if is_local { &current.0 } else { &remote.0 };
+        let size = stance_size(stance);
+        let color = stance_color(stance, is_local);
+        if sprite.custom_size != Some(size) || sprite.color != color {
+            sprite.custom_size = Some(size);
+            sprite.color = color;
+        }
+
+        let mut outlines = q_outline.iter_many_mut(children);
+        while let Some(mut outline) = outlines.fetch_next() {
+            let size = Some(size + Vec2::splat(STANCE_OUTLINE * 2.0));
+            let color = stance_outline(stance);
+            if outline.custom_size != size || outline.color != color {
+                outline.custom_size = size;
+                outline.color = color;
+            }
+        }
+    }
+}
@@ -1,20 +1,22 @@
 use crate::{
-    components::PlayerMarker,
+    components::{NameTag, PlayerMarker},
     events::{PlayerDamagedEvent, PlayerDied, PlayerLeftEvent},
-    resources::HpUiMap,
+    resources::{HpUiMap, PlayerNames},
 };
 use bevy::prelude::*;
 
 pub fn sync_hp_ui_position(
-    player_query: Query<(&Transform, &PlayerMarker), With<PlayerMarker>>,
+    player_query: Query<(&Transform, &Visibility, &PlayerMarker), With<PlayerMarker>>,
     mut hp_ui_map: ResMut<HpUiMap>,
-    mut ui_tf_query: Query<&mut Transform, Without<PlayerMarker>>,
+    mut ui_tf_query: Query<(&mut Transform, &mut Visibility), Without<PlayerMarker>>,
 ) {
-    for (player_tf, marker) in player_query.iter() {
+    for (player_tf, player_vis, marker) in player_query.iter() {
         if let Some(&ui_ent) = hp_ui_map.0.get(&marker.0) {
-            if let Ok(mut ui_tf) = ui_tf_query.get_mut(ui_ent) {
+            if let Ok((mut ui_tf, mut ui_vis)) = ui_tf_query.get_mut(ui_ent) {
                 ui_tf.translation.x = player_tf.translation.x;
                 ui_tf.translation.y = player_tf.translation.y + 32.0;
+                // игрок скрыт (вне видимости) — и полоска HP вместе с ним
+                ui_vis.set_if_neq(*player_vis);
             }
         }
     }
@@ -34,6 +36,15 @@ pub fn update_hp_text_from_event(
     }
 }
 
+/// Ники над игроками: на появлении таблички и когда сервер прислал новые
+pub fn update_name_tags(names: Res<PlayerNames>, mut q: Query<(Ref<NameTag>, &mut Text2d)>) {
+    for (tag, mut text) in &mut q {
+        if names.is_changed() || tag.is_added() {
+            text.0 = names.display(tag.0);
+        }
+    }
+}
+
 pub fn cleanup_hp_ui_on_player_remove(
     mut commands: Commands,
     mut hp_ui_map: ResMut<HpUiMap>,
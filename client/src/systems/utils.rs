@@ -1,7 +1,8 @@
 use bevy::prelude::*;
-use protocol::constants::{MOVE_SPEED, TICK_DT};
+use protocol::constants::{HITBOX_RADIUS, MOVE_SPEED, PLAYER_SIZE, TICK_DT};
 use protocol::messages::{InputState, Stance};
 
+use crate::components::NameTag;
 use crate::systems::level::Wall;
 
 pub fn time_in_seconds() -> f64 {
@@ -10,14 +11,32 @@ pub fn time_in_seconds() -> f64 {
     now.as_secs_f64()
 }
 
-pub fn stance_color(s: &Stance) -> Color {
+/// Свой игрок — в зелёных тонах, остальные — в синих; чем ниже стойка, тем темнее
+pub fn stance_color(s: &Stance, is_local: bool) -> Color {
+    match (s, is_local) {
+        (Stance::Standing, true) => Color::srgb(0.20, 1.00, 0.20),
+        (Stance::Crouching, true) => Color::srgb(0.15, 0.85, 1.00),
+        (Stance::Prone, true) => Color::srgb(0.00, 0.60, 0.60),
+        (Stance::Standing, false) => Color::srgb(0.00, 0.00, 1.00),
+        (Stance::Crouching, false) => Color::srgb(0.30, 0.30, 0.85),
+        (Stance::Prone, false) => Color::srgb(0.20, 0.20, 0.55),
+    }
+}
+
+/// Обводка одинакова для всех: по ней стойку видно и у врага, и у союзника
+pub fn stance_outline(s: &Stance) -> Color {
     match s {
-        Stance::Standing => Color::srgb(0.20, 1.00, 0.20),
-        Stance::Crouching => Color::srgb(0.15, 0.85, 1.00),
-        Stance::Prone => Color::srgb(0.00, 0.60, 0.60),
+        Stance::Standing => Color::srgb(0.05, 0.05, 0.05),
+        Stance::Crouching => Color::srgb(1.00, 0.85, 0.20),
+        Stance::Prone => Color::srgb(1.00, 0.45, 0.10),
     }
 }
 
+/// Спрайт игрока сжимается вместе с хитбоксом: присевшего и лежащего видно издалека
+pub fn stance_size(s: &Stance) -> Vec2 {
+    Vec2::splat(PLAYER_SIZE * s.hitbox_radius() / HITBOX_RADIUS)
+}
+
 pub fn lerp_angle(a: f32, b: f32, t: f32) -> f32 {
     let mut diff = (b - a) % std::f32::consts::TAU;
     if diff.abs() > std::f32::consts::PI {
@@ -31,12 +50,26 @@ pub fn spawn_hp_ui(commands: &mut Commands, player_id: u64, hp: u32, font: Handl
         .spawn((
             Text2d(format!("{} HP", hp)),
             TextFont {
-                font: font.into(),
+                font: font.clone(),
                 font_size: 14.0,
                 ..Default::default()
             },
             TextColor(Color::WHITE.into()),
         ))
+        .with_children(|parent| {
+            // текст заполнит update_name_tags, когда придёт ник
+            parent.spawn((
+                Text2d::new(""),
+                TextFont {
+                    font,
+                    font_size: 14.0,
+                    ..Default::default()
+                },
+                TextColor(Color::srgb(0.85, 0.9, 1.0)),
+                Transform::from_xyz(0.0, 16.0, 0.0),
+                NameTag(player_id),
+            ));
+        })
         .id()
 }
 
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+use crate::resources::{UiFont, weapons::AmmoState};
+
+#[derive(Component)]
+pub struct AmmoText;
+
+pub fn setup_ammo_ui(mut commands: Commands, font: Res<UiFont>) {
+    // счётчик патронов над полоской гранаты
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font: font.0.clone(),
+            font_size: 22.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(44.0),
+            right: Val::Px(20.0),
+            ..default()
+        },
+        AmmoText,
+    ));
+}
+
+pub fn update_ammo_ui(ammo: Res<AmmoState>, mut query: Query<&mut Text, With<AmmoText>>) {
+    if !ammo.is_changed() {
+        return;
+    }
+
+    for mut text in &mut query {
+        let name = ammo.weapon.def().name;
+        text.0 = if ammo.reloading {
+            format!("{name}  перезарядка… / {}", ammo.reserve)
+        } else {
+            format!("{name}  {} / {}", ammo.mag, ammo.reserve)
+        };
+    }
+}
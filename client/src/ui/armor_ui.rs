@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use crate::resources::{MyArmor, UiFont};
+
+#[derive(Component)]
+pub struct ArmorText;
+
+pub fn setup_armor_ui(mut commands: Commands, font: Res<UiFont>) {
+    // броня — в левом нижнем углу, напротив патронов
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font: font.0.clone(),
+            font_size: 22.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.55, 0.75, 1.0)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(44.0),
+            left: Val::Px(20.0),
+            ..default()
+        },
+        ArmorText,
+    ));
+}
+
+pub fn update_armor_ui(armor: Res<MyArmor>, mut query: Query<&mut Text, With<ArmorText>>) {
+    if !armor.is_changed() {
+        return;
+    }
+
+    for mut text in &mut query {
+        text.0 = if armor.0 > 0 {
+            format!("Броня {}", armor.0)
+        } else {
+            String::new()
+        };
+    }
+}
@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+use protocol::messages::DeathCause;
+
+use crate::{
+    constants::{KILL_FEED_FADE, KILL_FEED_MAX, KILL_FEED_TTL},
+    events::PlayerDied,
+    resources::{MyPlayer, PlayerNames, UiFont},
+};
+
+#[derive(Component)]
+pub struct KillFeedRoot;
+
+#[derive(Component)]
+pub struct KillFeedEntry {
+    pub timer: Timer,
+}
+
+pub fn setup_kill_feed_ui(mut commands: Commands) {
+    // колонка в правом верхнем углу, новые строки снизу
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            right: Val::Px(20.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::FlexEnd,
+            row_gap: Val::Px(4.0),
+            ..default()
+        },
+        KillFeedRoot,
+    ));
+}
+
+/// Метка оружия в строке фида. Иконок оружия в assets нет, поэтому вместо картинки — текст
+fn cause_label(cause: DeathCause) -> String {
+    match cause {
+        DeathCause::Weapon(kind) => format!("[{}]", kind.def().name),
+        DeathCause::Grenade => "[Граната]".to_string(),
+    }
+}
+
+pub fn push_kill_feed(
+    mut commands: Commands,
+    mut ev_died: EventReader<PlayerDied>,
+    my: Res<MyPlayer>,
+    names: Res<PlayerNames>,
+    font: Res<UiFont>,
+    root_q: Query<Entity, With<KillFeedRoot>>,
+    mut entries_q: Query<&mut KillFeedEntry>,
+) {
+    let Ok(root) = root_q.single() else {
+        return;
+    };
+    let events: Vec<&PlayerDied> = ev_died.read().collect();
+    if events.is_empty() {
+        return;
+    }
+    let name = |id: u64| {
+        if id == my.id {
+            "Вы".to_string()
+        } else {
+            names.display(id)
+        }
+    };
+
+    // лишние строки не удаляем здесь, а дожигаем таймер — despawn только в update_kill_feed
+    let mut shown: Vec<_> = entries_q
+        .iter_mut()
+        .filter(|e| e.timer.remaining_secs() > 0.0)
+        .collect();
+    shown.sort_by(|a, b| b.timer.elapsed().cmp(&a.timer.elapsed()));
+    let excess = (shown.len() + events.len()).saturating_sub(KILL_FEED_MAX);
+    for entry in shown.iter_mut().take(excess) {
+        let full = entry.timer.duration();
+        entry.timer.set_elapsed(full);
+    }
+
+    // если смертей за кадр больше, чем строк, — старые из них и не показываем
+    let skip = excess.saturating_sub(shown.len());
+    for ev in events.into_iter().skip(skip) {
+        let label = cause_label(ev.cause);
+        let line = match ev.killer.filter(|&k| k != ev.victim) {
+            Some(killer) => format!("{}  {label}  {}", name(killer), name(ev.victim)),
+            None => format!("{label}  {}", name(ev.victim)),
+        };
+        let involves_me = ev.victim == my.id || ev.killer == Some(my.id);
+        let color = if involves_me {
+            Color::srgb(1.0, 0.85, 0.3)
+        } else {
+            Color::WHITE
+        };
+
+        let entry = commands
+            .spawn((
+                Text::new(line),
+                TextFont {
+                    font: font.0.clone(),
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(color),
+                Node {
+                    padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+                KillFeedEntry {
+                    timer: Timer::from_seconds(KILL_FEED_TTL, TimerMode::Once),
+                },
+            ))
+            .id();
+        commands.entity(root).add_child(entry);
+    }
+}
+
+pub fn update_kill_feed(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q: Query<(
+        Entity,
+        &mut KillFeedEntry,
+        &mut TextColor,
+        &mut BackgroundColor,
+    )>,
+) {
+    for (ent, mut entry, mut color, mut bg) in q.iter_mut() {
+        entry.timer.tick(time.delta());
+        if entry.timer.finished() {
+            commands.entity(ent).despawn();
+            continue;
+        }
+
+        let alpha = (entry.timer.remaining_secs() / KILL_FEED_FADE).min(1.0);
+        color.0.set_alpha(alpha);
+        bg.0.set_alpha(alpha * 0.5);
+    }
+}
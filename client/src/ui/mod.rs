@@ -1,3 +1,8 @@
 pub mod grenade_ui;
 pub mod update_grenade_cooldown_ui;
-pub mod components;
\ No newline at end of file
+pub mod components;
+pub mod ammo_ui;
+pub mod armor_ui;
+pub mod round_ui;
+pub mod scoreboard_ui;
+pub mod kill_feed_ui;
\ No newline at end of file
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+use protocol::messages::{RoundPhase, Team};
+
+use crate::{
+    resources::{
+        MyPlayer, UiFont,
+        rounds::{PlayerTeams, RoundHud},
+    },
+    systems::utils::time_in_seconds,
+};
+
+#[derive(Component)]
+pub struct RoundText;
+
+pub fn setup_round_ui(mut commands: Commands, font: Res<UiFont>) {
+    // строка раунда по центру сверху; пустая, пока сервер в дезматче
+    commands
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font: font.0.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                RoundText,
+            ));
+        });
+}
+
+pub fn team_label(team: Team) -> &'static str {
+    match team {
+        Team::Terrorists => "T",
+        Team::CounterTerrorists => "CT",
+    }
+}
+
+pub fn update_round_ui(
+    round: Res<RoundHud>,
+    teams: Res<PlayerTeams>,
+    my: Res<MyPlayer>,
+    mut query: Query<&mut Text, With<RoundText>>,
+) {
+    let Some(info) = round.info.as_ref() else {
+        return;
+    };
+
+    let left = round.time_left(time_in_seconds()).ceil() as u32;
+    let timer = format!("{}:{:02}", left / 60, left % 60);
+    let phase = match info.phase {
+        RoundPhase::Warmup => "Разминка: ждём игроков в обеих командах".to_string(),
+        RoundPhase::Freeze => format!("Заморозка {timer}"),
+        RoundPhase::Live => timer,
+        RoundPhase::Ended { winner: Some(team) } => format!("Победа {}", team_label(team)),
+        RoundPhase::Ended { winner: None } => "Ничья".to_string(),
+    };
+    let me = teams
+        .0
+        .get(&my.id)
+        .map(|&team| format!("  ·  вы за {}", team_label(team)))
+        .unwrap_or_default();
+
+    for mut text in &mut query {
+        text.0 = format!(
+            "Раунд {}  ·  T {} : {} CT  ·  {}{}",
+            info.round, info.score_t, info.score_ct, phase, me
+        );
+    }
+}
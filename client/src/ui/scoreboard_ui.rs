@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use crate::{
+    resources::{MyPlayer, PlayerNames, UiFont, scoreboard::Scoreboard},
+    ui::round_ui::team_label,
+};
+
+#[derive(Component)]
+pub struct ScoreboardRoot;
+
+#[derive(Component)]
+pub struct ScoreboardGrid;
+
+const COLUMNS: [(&str, f32); 6] = [
+    ("Игрок", 160.0),
+    ("Команда", 90.0),
+    ("K", 40.0),
+    ("D", 40.0),
+    ("A", 40.0),
+    ("Пинг", 70.0),
+];
+
+pub fn setup_scoreboard_ui(mut commands: Commands) {
+    // таблица по центру экрана; видна, пока зажат Tab
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                top: Val::Px(80.0),
+                justify_content: JustifyContent::Center,
+                display: Display::None,
+                ..default()
+            },
+            ScoreboardRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    display: Display::Grid,
+                    grid_template_columns: COLUMNS
+                        .iter()
+                        .map(|&(_, width)| GridTrack::px(width))
+                        .collect(),
+                    row_gap: Val::Px(4.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+                ScoreboardGrid,
+            ));
+        });
+}
+
+pub fn toggle_scoreboard_ui(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut query: Query<&mut Node, With<ScoreboardRoot>>,
+) {
+    let display = if keys.pressed(KeyCode::Tab) {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in &mut query {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
+/// Пересобираем ячейки, когда пришла новая таблица
+pub fn update_scoreboard_ui(
+    mut commands: Commands,
+    scoreboard: Res<Scoreboard>,
+    my: Res<MyPlayer>,
+    names: Res<PlayerNames>,
+    font: Res<UiFont>,
+    query: Query<Entity, With<ScoreboardGrid>>,
+) {
+    if !scoreboard.is_changed() {
+        return;
+    }
+
+    // больше фрагов — выше, при равенстве выше тот, кто реже умирал
+    let mut entries: Vec<_> = scoreboard.0.iter().collect();
+    entries.sort_by_key(|e| (std::cmp::Reverse(e.kills), e.deaths, e.id));
+
+    for grid in &query {
+        commands.entity(grid).despawn_related::<Children>();
+        commands.entity(grid).with_children(|parent| {
+            let mut cell = |text: String, color: Color| {
+                parent.spawn((
+                    Text::new(text),
+                    TextFont {
+                        font: font.0.clone(),
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    TextColor(color),
+                ));
+            };
+
+            let header = Color::srgb(0.7, 0.7, 0.7);
+            for (title, _) in COLUMNS {
+                cell(title.to_string(), header);
+            }
+
+            for entry in &entries {
+                let me = entry.id == my.id;
+                let color = if me {
+                    Color::srgb(1.0, 0.85, 0.3)
+                } else {
+                    Color::WHITE
+                };
+                let suffix = if me { " (вы)" } else { "" };
+                cell(format!("{}{suffix}", names.display(entry.id)), color);
+                cell(entry.team.map_or("—", team_label).to_string(), color);
+                cell(entry.kills.to_string(), color);
+                cell(entry.deaths.to_string(), color);
+                cell(entry.assists.to_string(), color);
+                cell(format!("{} мс", entry.ping_ms), color);
+            }
+        });
+    }
+}
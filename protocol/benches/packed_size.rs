@@ -0,0 +1,115 @@
+//! Размер и скорость битовой упаковки против serde + bincode, которым сообщения шли раньше.
+//! Запуск: `cargo bench -p protocol --bench packed_size`
+use glam::Vec2;
+use protocol::messages::{C2S, InputState, PlayerDelta, S2C, SnapshotDelta, Stance};
+use protocol::packed::{PackedInput, PackedSnapshot};
+use std::hint::black_box;
+use std::time::Instant;
+
+const HALF: Vec2 = Vec2::new(640.0, 480.0);
+const ITERS: u32 = 100_000;
+
+fn snapshot(players: u64, full: bool) -> SnapshotDelta {
+    let changed = (0..players)
+        .map(|i| {
+            let t = i as f32;
+            PlayerDelta {
+                id: i + 1,
+                x: Some(-600.0 + t * 37.3),
+                y: Some(450.0 - t * 29.1),
+                rotation: Some((t * 0.7).sin() * 3.0),
+                // в дельте у большинства меняются только позиция и поворот
+                stance: full.then_some(Stance::Standing),
+                hp: full.then_some(100 - i as i32),
+            }
+        })
+        .collect();
+    SnapshotDelta {
+        tick: 12_345,
+        baseline: (!full).then_some(12_340),
+        server_time: 192.84375,
+        changed,
+        removed: Vec::new(),
+        ack_seq: 12_001,
+    }
+}
+
+fn input() -> InputState {
+    InputState {
+        seq: 12_001,
+        up: true,
+        down: false,
+        left: false,
+        right: true,
+        rotation: 1.234,
+        stance: Stance::Crouching,
+        timestamp: 1_700_000_123.456,
+    }
+}
+
+fn bincode_len<T: serde::Serialize>(msg: &T) -> (usize, usize) {
+    let legacy = bincode::serde::encode_to_vec(msg, bincode::config::legacy()).unwrap();
+    let standard = bincode::serde::encode_to_vec(msg, bincode::config::standard()).unwrap();
+    (legacy.len(), standard.len())
+}
+
+fn row(name: &str, before: (usize, usize), after: usize) {
+    println!(
+        "{name:<28} {:>8} {:>10} {:>8} {:>7.1}x",
+        before.0,
+        before.1,
+        after,
+        before.0 as f32 / after as f32
+    );
+}
+
+fn time_per_iter(mut f: impl FnMut()) -> f64 {
+    let start = Instant::now();
+    for _ in 0..ITERS {
+        f();
+    }
+    start.elapsed().as_secs_f64() * 1e9 / ITERS as f64
+}
+
+fn main() {
+    println!(
+        "{:<28} {:>8} {:>10} {:>8} {:>8}",
+        "сообщение", "legacy", "standard", "packed", "сжатие"
+    );
+
+    for players in [1, 8, 32] {
+        for full in [true, false] {
+            let delta = snapshot(players, full);
+            // у упакованного считаем и тег S2C, у исходного — нет: сравнение не в пользу упаковки
+            let before = bincode_len(&delta);
+            let after = bincode_len(&S2C::Snapshot(PackedSnapshot::pack(&delta, HALF).unwrap())).0;
+            let kind = if full { "полный" } else { "дельта" };
+            row(&format!("снапшот {kind}, {players} игр."), before, after);
+        }
+    }
+
+    let inp = input();
+    row(
+        "инпут",
+        bincode_len(&inp),
+        bincode_len(&C2S::Input(PackedInput::pack(&inp))).0,
+    );
+
+    println!();
+    let delta = snapshot(32, true);
+    let packed = PackedSnapshot::pack(&delta, HALF).unwrap();
+    let pack_ns = time_per_iter(|| {
+        black_box(PackedSnapshot::pack(black_box(&delta), HALF));
+    });
+    let unpack_ns = time_per_iter(|| {
+        black_box(black_box(&packed).unpack(HALF));
+    });
+    let bincode_ns = time_per_iter(|| {
+        black_box(
+            bincode::serde::encode_to_vec(black_box(&delta), bincode::config::legacy()).unwrap(),
+        );
+    });
+    println!(
+        "полный снапшот, 32 игр.: pack {pack_ns:.0} нс, unpack {unpack_ns:.0} нс, bincode {bincode_ns:.0} нс"
+    );
+}
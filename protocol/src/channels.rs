@@ -1,10 +1,12 @@
-use crate::constants::{CH_C2S, CH_S2C};
+use crate::constants::{CH_C2S, CH_S2C, CH_S2C_UNRELIABLE};
 
 /// Описание надёжности канала без привязки к Quinnet
 #[derive(Clone, Copy, Debug)]
 pub enum Reliability {
     OrderedReliable { max_frame_size: usize },
     UnorderedReliable { max_frame_size: usize },
+    // без гарантий доставки и порядка; сообщение должно влезать в датаграмму
+    Unreliable,
 }
 
 /// Описание канала протокола
@@ -14,8 +16,9 @@ pub struct ChannelDesc {
     pub reliability: Reliability,
 }
 
-/// Настройка каналов (client → server, server → client)
+/// Настройка каналов (client → server, server → client); id канала — его индекс в списке
 pub const CHANNELS: &[ChannelDesc] = &[
     ChannelDesc { id: CH_C2S, reliability: Reliability::OrderedReliable { max_frame_size: 16_000 } },
     ChannelDesc { id: CH_S2C, reliability: Reliability::OrderedReliable { max_frame_size: 16_000 } },
+    ChannelDesc { id: CH_S2C_UNRELIABLE, reliability: Reliability::Unreliable },
 ];
\ No newline at end of file
@@ -1,16 +1,42 @@
+// Версия сетевого протокола: повышаем при любом несовместимом изменении C2S/S2C
+pub const PROTOCOL_VERSION: u32 = 20;
+
+// Capability-флаги (битовая маска в Hello/Welcome)
+pub const CAP_GRENADE_SYNC: u32 = 1 << 0; // клиент понимает GrenadeSync
+// что умеет эта сборка; сервер отвечает пересечением со своими
+pub const SUPPORTED_CAPABILITIES: u32 = CAP_GRENADE_SYNC;
+
 // Channel IDs
 pub const CH_C2S: u8 = 0;
 pub const CH_S2C: u8 = 1;
+pub const CH_S2C_UNRELIABLE: u8 = 2; // снапшоты и косметика: потерю переживём, ждать её не надо
 
 // Timing & movement constants
 pub const TICK_DT: f32 = 0.015; // 64Hz
 pub const MOVE_SPEED: f32 = 300.0;
+// сколько инпутов одного клиента сервер применяет за тик; остальные ждут следующего
+pub const MAX_INPUTS_PER_TICK: usize = 4;
+// длиннее очередь не растёт: самые старые инпуты выкидываем (~0.25 с при 64 Гц)
+pub const MAX_PENDING_INPUTS: usize = 16;
 
 // Hit detection, радиус precise при стрельбе
 pub const HITBOX_RADIUS: f32 = 20.0;
-// от этого зависит обсчет попаданий (на такой дистанции)
-// дальность стрельбы
-pub const MAX_RAY_LEN: f32 = 3000.0;
+
+// Стойка: доля MOVE_SPEED, радиус хитбокса и множитель разброса (стоя — 1.0 / HITBOX_RADIUS)
+pub const CROUCH_SPEED_MULT: f32 = 0.6;
+pub const PRONE_SPEED_MULT: f32 = 0.3;
+pub const CROUCH_HITBOX_RADIUS: f32 = 15.0;
+pub const PRONE_HITBOX_RADIUS: f32 = 11.0;
+pub const CROUCH_SPREAD_MULT: f32 = 0.7;
+pub const PRONE_SPREAD_MULT: f32 = 0.4;
+
+// Здоровье, броня и предметы на карте ('H' — аптечка, 'A' — бронежилет)
+pub const MAX_HP: i32 = 100;
+pub const HEALTH_PACK_HP: i32 = 50;
+pub const MAX_ARMOR: i32 = 100;
+pub const ARMOR_ABSORB: f32 = 0.5; // доля урона, которую берёт на себя броня, пока она есть
+pub const PICKUP_RADIUS: f32 = 24.0; // от центра игрока до центра предмета
+pub const PICKUP_RESPAWN_SECS: f64 = 20.0;
 
 // Timeout
 pub const TIMEOUT_SECS: f64 = 3.0;
@@ -18,6 +44,11 @@ pub const TIMEOUT_SECS: f64 = 3.0;
 // Respawn
 pub const RESPAWN_COOLDOWN: f64 = 5.0;
 
+// Раунды (режим --mode rounds), secs
+pub const ROUND_FREEZE_TIME: f64 = 5.0; // заморозка в начале раунда
+pub const ROUND_TIME: f64 = 115.0; // вышло время — победа CT
+pub const ROUND_END_TIME: f64 = 5.0; // пауза после победы
+
 // Скорость полёта гранаты (пикселей в секунду)
 pub const GRENADE_SPEED: f32 = 300.0;
 // Время до взрыва
@@ -29,7 +60,20 @@ pub const GRENADE_USAGE_COOLDOWN: f64 = 2.0;
 
 pub const GRENADE_DAMAGE_COEFF: f32 = 3.0;
 
-pub const SHOOT_RIFLE_DAMAGE: f32 = 20.0;
+// Счёт: ассист — если нанёс жертве не меньше стольких HP, но добил другой
+pub const ASSIST_MIN_DAMAGE: i32 = 40;
+// как часто сервер рассылает таблицу счёта, secs
+pub const SCOREBOARD_INTERVAL: f32 = 1.0;
+
+// Ник игрока, в символах (не байтах)
+pub const NICKNAME_MAX_LEN: usize = 16;
+
+// Параметры оружия (урон, дальность, темп, магазин) — в weapons::WEAPONS
+// на столько выстрел может прийти раньше ровного темпа (джиттер, пачка в одном кадре
+// сервера); средний темп всё равно не выше оружейного, secs
+pub const FIRE_JITTER_SECS: f64 = 0.2;
+// очередь (рост разброса и отдача) прерывается паузой дольше темпа оружия на столько, secs
+pub const SPRAY_RESET_SECS: f64 = 0.3;
 
 // размер уровня (по центру, координаты в world space)
 pub const LEVEL_WIDTH: f32 = 1200.0;
@@ -37,6 +81,8 @@ pub const LEVEL_HEIGHT: f32 = 800.0;
 pub const WALL_THICKNESS: f32 = 40.0;
 
 pub const PLAYER_SIZE: f32 = 32.0;
+// врага шлём, если виден его центр или точки на столько в стороны: не выскочит из-за угла
+pub const LOS_CULL_MARGIN: f32 = PLAYER_SIZE;
 
 pub const TILE_SIZE: f32 = 32.0;
 
@@ -47,6 +93,10 @@ pub const SEPARATION_EPS: f32 = 0.5; // «волосок», чтобы не за
 
 // Физика полёта / отскока (должны совпадать на клиенте и сервере)
 pub const GRENADE_AIR_DRAG_PER_SEC: f32 = 0.06; // 6%/сек экспоненциально
+// (1 - GRENADE_AIR_DRAG_PER_SEC)^TICK_DT заранее: powf на разных платформах может разойтись в последнем бите
+pub const GRENADE_DRAG_PER_TICK: f32 = 0.999_072_3;
 pub const GRENADE_RESTITUTION: f32    = 0.5;    // упругость отражения
 pub const GRENADE_BOUNCE_DAMPING: f32 = 0.70;   // доп. гашение на ударе
 pub const GRENADE_STOP_SPEED: f32     = 30.0;   // ниже — считаем, что остановилась
+// клиент симулирует полёт сам, GrenadeSync — только редкая сверка, secs
+pub const GRENADE_SYNC_INTERVAL: f32 = 0.5;
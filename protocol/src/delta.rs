@@ -0,0 +1,222 @@
+use crate::messages::{PlayerDelta, PlayerSnapshot, SnapshotDelta, WorldSnapshot};
+
+/// Дельта `current` к `baseline`; без базы — полный снапшот (все поля всех игроков)
+pub fn encode_delta(current: &WorldSnapshot, baseline: Option<&WorldSnapshot>) -> SnapshotDelta {
+    let find = |id: u64| baseline.and_then(|b| b.players.iter().find(|p| p.id == id));
+
+    let changed = current
+        .players
+        .iter()
+        .filter_map(|p| {
+            let old = find(p.id);
+            let differs = |f: fn(&PlayerSnapshot) -> f32| old.is_none_or(|o| f(o) != f(p));
+            let delta = PlayerDelta {
+                id: p.id,
+                x: differs(|s| s.x).then_some(p.x),
+                y: differs(|s| s.y).then_some(p.y),
+                rotation: differs(|s| s.rotation).then_some(p.rotation),
+                stance: old
+                    .is_none_or(|o| o.stance != p.stance)
+                    .then(|| p.stance.clone()),
+                hp: old.is_none_or(|o| o.hp != p.hp).then_some(p.hp),
+            };
+            // новичка шлём целиком, остальных — только если что-то поменялось
+            let any = delta.x.is_some()
+                || delta.y.is_some()
+                || delta.rotation.is_some()
+                || delta.stance.is_some()
+                || delta.hp.is_some();
+            (old.is_none() || any).then_some(delta)
+        })
+        .collect();
+
+    let removed = baseline
+        .map(|b| {
+            b.players
+                .iter()
+                .filter(|o| !current.players.iter().any(|p| p.id == o.id))
+                .map(|o| o.id)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    SnapshotDelta {
+        tick: current.tick,
+        baseline: baseline.map(|b| b.tick),
+        server_time: current.server_time,
+        changed,
+        removed,
+        ack_seq: current.ack_seq,
+    }
+}
+
+/// Собираем полный снапшот из дельты; None — базы нет или пришла не та, что нужна
+pub fn apply_delta(
+    baseline: Option<&WorldSnapshot>,
+    delta: SnapshotDelta,
+) -> Option<WorldSnapshot> {
+    let mut players: Vec<PlayerSnapshot> = match (delta.baseline, baseline) {
+        (None, _) => Vec::new(),
+        (Some(tick), Some(b)) if b.tick == tick => b
+            .players
+            .iter()
+            .filter(|p| !delta.removed.contains(&p.id))
+            .cloned()
+            .collect(),
+        _ => return None,
+    };
+
+    for d in delta.changed {
+        match players.iter_mut().find(|p| p.id == d.id) {
+            Some(p) => {
+                p.x = d.x.unwrap_or(p.x);
+                p.y = d.y.unwrap_or(p.y);
+                p.rotation = d.rotation.unwrap_or(p.rotation);
+                p.stance = d.stance.unwrap_or_else(|| p.stance.clone());
+                p.hp = d.hp.unwrap_or(p.hp);
+            }
+            None => players.push(PlayerSnapshot {
+                id: d.id,
+                x: d.x.unwrap_or_default(),
+                y: d.y.unwrap_or_default(),
+                rotation: d.rotation.unwrap_or_default(),
+                stance: d.stance.unwrap_or_default(),
+                hp: d.hp.unwrap_or_default(),
+            }),
+        }
+    }
+
+    Some(WorldSnapshot {
+        players,
+        tick: delta.tick,
+        server_time: delta.server_time,
+        ack_seq: delta.ack_seq,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::Stance;
+    use proptest::prelude::*;
+
+    fn player(id: u64, x: f32, hp: i32) -> PlayerSnapshot {
+        PlayerSnapshot {
+            id,
+            x,
+            y: -40.0,
+            rotation: 1.0,
+            stance: Stance::Standing,
+            hp,
+        }
+    }
+
+    fn world(tick: u32, players: Vec<PlayerSnapshot>) -> WorldSnapshot {
+        WorldSnapshot {
+            players,
+            tick,
+            server_time: tick as f64 * 0.015,
+            ack_seq: tick * 2,
+        }
+    }
+
+    // порядок игроков после сборки не гарантирован — сравниваем по id
+    fn sorted(mut snap: WorldSnapshot) -> WorldSnapshot {
+        snap.players.sort_by_key(|p| p.id);
+        snap
+    }
+
+    #[test]
+    fn unchanged_fields_are_omitted() {
+        let base = world(10, vec![player(1, 5.0, 100), player(2, 7.0, 100)]);
+        let cur = world(11, vec![player(1, 6.0, 100), player(2, 7.0, 100)]);
+        let delta = encode_delta(&cur, Some(&base));
+
+        assert_eq!(delta.baseline, Some(10));
+        // второй не двигался — его в дельте нет вовсе
+        assert_eq!(delta.changed.len(), 1);
+        let d = &delta.changed[0];
+        assert_eq!((d.id, d.x), (1, Some(6.0)));
+        assert!(d.y.is_none() && d.rotation.is_none() && d.stance.is_none() && d.hp.is_none());
+        assert!(delta.removed.is_empty());
+        assert_eq!(apply_delta(Some(&base), delta), Some(cur));
+    }
+
+    #[test]
+    fn joined_player_is_sent_whole_and_left_one_removed() {
+        let base = world(10, vec![player(1, 5.0, 100), player(2, 7.0, 100)]);
+        let cur = world(11, vec![player(1, 5.0, 100), player(3, 0.0, 0)]);
+        let delta = encode_delta(&cur, Some(&base));
+
+        assert_eq!(delta.removed, vec![2]);
+        assert_eq!(delta.changed.len(), 1);
+        // даже нулевые поля новичка — явно, а не «как в базе»
+        let d = &delta.changed[0];
+        assert_eq!((d.id, d.x, d.hp), (3, Some(0.0), Some(0)));
+        assert!(d.y.is_some() && d.rotation.is_some() && d.stance.is_some());
+        assert_eq!(apply_delta(Some(&base), delta), Some(cur));
+    }
+
+    #[test]
+    fn wrong_or_missing_baseline_needs_full_snapshot() {
+        let base = world(10, vec![player(1, 5.0, 100)]);
+        let cur = world(12, vec![player(1, 6.0, 90)]);
+        let delta = encode_delta(&cur, Some(&base));
+
+        // у клиента нет базы или она от другого тика — дельту не собрать
+        assert_eq!(apply_delta(None, delta.clone()), None);
+        let other = world(11, base.players.clone());
+        assert_eq!(apply_delta(Some(&other), delta), None);
+
+        // полный снапшот собирается с любой базой и без неё
+        let full = encode_delta(&cur, None);
+        assert_eq!(full.baseline, None);
+        assert!(full.removed.is_empty());
+        assert_eq!(apply_delta(None, full.clone()), Some(cur.clone()));
+        assert_eq!(apply_delta(Some(&other), full), Some(cur));
+    }
+
+    // мало разных значений — чтобы поля часто совпадали с базой
+    fn arb_player(id: u64) -> impl Strategy<Value = PlayerSnapshot> {
+        let coord = prop::sample::select(vec![-300.0f32, 0.0, 12.5, 299.75]);
+        (
+            coord.clone(),
+            coord,
+            prop::sample::select(vec![0.0f32, 1.5, -3.1]),
+            prop::sample::select(vec![Stance::Standing, Stance::Crouching, Stance::Prone]),
+            prop::sample::select(vec![0, 35, 100]),
+        )
+            .prop_map(move |(x, y, rotation, stance, hp)| PlayerSnapshot {
+                id,
+                x,
+                y,
+                rotation,
+                stance,
+                hp,
+            })
+    }
+
+    // игроки с id из 0..6: часть есть в обоих снапшотах, часть пришла или ушла
+    fn arb_world(tick: u32) -> impl Strategy<Value = WorldSnapshot> {
+        prop::collection::vec(any::<bool>(), 6)
+            .prop_flat_map(|present| {
+                (0..present.len())
+                    .filter(|&id| present[id])
+                    .map(|id| arb_player(id as u64))
+                    .collect::<Vec<_>>()
+            })
+            .prop_map(move |players| world(tick, players))
+    }
+
+    proptest! {
+        #[test]
+        fn delta_round_trip(base in arb_world(100), cur in arb_world(105)) {
+            let delta = encode_delta(&cur, Some(&base));
+            let back = apply_delta(Some(&base), delta).unwrap();
+            prop_assert_eq!(sorted(back), sorted(cur.clone()));
+
+            let full = apply_delta(None, encode_delta(&cur, None)).unwrap();
+            prop_assert_eq!(sorted(full), sorted(cur));
+        }
+    }
+}
@@ -1,6 +1,10 @@
 pub mod constants;
 pub mod channels;
+pub mod delta;
 pub mod messages;
+pub mod map;
+pub mod packed;
+pub mod weapons;
 
 // Адаптер для Quinnet (включать с фичей "quinnet")
 #[cfg(feature = "quinnet")]
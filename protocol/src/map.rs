@@ -0,0 +1,414 @@
+use crate::constants::TILE_SIZE;
+use glam::Vec2;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_MAP: &str = "arena";
+
+/// Каталог с картами ищем при запуске: `assets/maps` рядом с бинарником,
+/// потом в текущем каталоге (так работает `cargo run` из корня репозитория)
+pub fn maps_dir() -> PathBuf {
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    exe_dir
+        .into_iter()
+        .chain(std::env::current_dir().ok())
+        .map(|dir| dir.join("assets").join("maps"))
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| PathBuf::from("assets/maps"))
+}
+
+/// Имя карты → путь к файлу в `maps_dir`; путь с `.txt` берём как есть
+pub fn map_path(maps_dir: &Path, name_or_path: &str) -> PathBuf {
+    if name_or_path.ends_with(".txt") {
+        PathBuf::from(name_or_path)
+    } else {
+        maps_dir.join(format!("{name_or_path}.txt"))
+    }
+}
+
+/// Клетка карты: '#' — стена, '.' — пусто, 'S' — спавн-поинт,
+/// 'T' / 'C' — зоны спавна террористов / спецназа (режим раундов),
+/// 'H' / 'A' — аптечка / бронежилет
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Empty,
+    Wall,
+    Spawn,
+    SpawnT,
+    SpawnCt,
+    HealthPack,
+    ArmorVest,
+}
+
+impl Tile {
+    pub fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '.' => Some(Tile::Empty),
+            '#' => Some(Tile::Wall),
+            'S' => Some(Tile::Spawn),
+            'T' => Some(Tile::SpawnT),
+            'C' => Some(Tile::SpawnCt),
+            'H' => Some(Tile::HealthPack),
+            'A' => Some(Tile::ArmorVest),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Empty => '.',
+            Tile::Wall => '#',
+            Tile::Spawn => 'S',
+            Tile::SpawnT => 'T',
+            Tile::SpawnCt => 'C',
+            Tile::HealthPack => 'H',
+            Tile::ArmorVest => 'A',
+        }
+    }
+
+    pub fn is_pickup(self) -> bool {
+        matches!(self, Tile::HealthPack | Tile::ArmorVest)
+    }
+}
+
+/// Прямоугольник клеток: левая нижняя клетка (x, y) и размер в клетках
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileRect {
+    pub x: usize,
+    pub y: usize,
+    pub w: usize,
+    pub h: usize,
+}
+
+impl TileRect {
+    /// Размер в мировых координатах
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.w as f32, self.h as f32) * TILE_SIZE
+    }
+}
+
+/// Жадно сливаем сплошные клетки в прямоугольники: тянем ряд вправо, потом вверх,
+/// пока следующий ряд под ним весь сплошной. Каждая клетка попадает ровно в один
+pub fn merge_rects(
+    width: usize,
+    height: usize,
+    solid: impl Fn(usize, usize) -> bool,
+) -> Vec<TileRect> {
+    let mut used = vec![false; width * height];
+    let free = |x: usize, y: usize, used: &[bool]| solid(x, y) && !used[y * width + x];
+    let mut rects = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            if !free(x, y, &used) {
+                continue;
+            }
+            let mut w = 1;
+            while x + w < width && free(x + w, y, &used) {
+                w += 1;
+            }
+            let mut h = 1;
+            while y + h < height && (x..x + w).all(|cx| free(cx, y + h, &used)) {
+                h += 1;
+            }
+            for cy in y..y + h {
+                used[cy * width + x..cy * width + x + w].fill(true);
+            }
+            rects.push(TileRect { x, y, w, h });
+        }
+    }
+    rects
+}
+
+/// Ошибка разбора карты; строки и столбцы считаются с 1
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "строка {}, столбец {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for MapError {}
+
+/// Прямоугольная карта из клеток; строка 0 файла — нижний ряд мира
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameMap {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    tiles: Vec<Tile>,
+}
+
+impl GameMap {
+    /// Разбор текстового формата: одна строка файла — один ряд клеток, все ряды одной ширины.
+    /// Пустые строки в конце файла игнорируются. Карта без единой точки спавна — ошибка.
+    pub fn parse(name: &str, src: &str) -> Result<Self, MapError> {
+        let lines: Vec<&str> = src.lines().map(|l| l.trim_end_matches('\r')).collect();
+        let height = lines
+            .iter()
+            .rposition(|l| !l.is_empty())
+            .map_or(0, |i| i + 1);
+        if height == 0 {
+            return Err(MapError {
+                line: 1,
+                column: 1,
+                message: "пустая карта".into(),
+            });
+        }
+
+        let width = lines[0].chars().count();
+        let mut tiles = Vec::with_capacity(width * height);
+        for (y, row) in lines[..height].iter().enumerate() {
+            let mut len = 0;
+            for (x, ch) in row.chars().enumerate() {
+                let tile = Tile::from_char(ch).ok_or_else(|| MapError {
+                    line: y + 1,
+                    column: x + 1,
+                    message: format!("неизвестный символ '{ch}'"),
+                })?;
+                if x >= width {
+                    return Err(MapError {
+                        line: y + 1,
+                        column: x + 1,
+                        message: format!("строка длиннее первой ({width} клеток)"),
+                    });
+                }
+                tiles.push(tile);
+                len += 1;
+            }
+            if len < width {
+                return Err(MapError {
+                    line: y + 1,
+                    column: len + 1,
+                    message: format!("строка короче первой ({len} из {width} клеток)"),
+                });
+            }
+        }
+
+        if !tiles
+            .iter()
+            .any(|t| matches!(t, Tile::Spawn | Tile::SpawnT | Tile::SpawnCt))
+        {
+            return Err(MapError {
+                line: 1,
+                column: 1,
+                message: "нет ни одной точки спавна ('S', 'T' или 'C')".into(),
+            });
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            width,
+            height,
+            tiles,
+        })
+    }
+
+    /// Читаем и разбираем файл; имя карты — имя файла без расширения
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let src = std::fs::read_to_string(path)
+            .map_err(|e| format!("не удалось прочитать {}: {e}", path.display()))?;
+        let name = path
+            .file_stem()
+            .map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+        Self::parse(&name, &src).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// Каноничный текст карты (ряды через '\n') — его и передаём клиентам
+    pub fn to_text(&self) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for row in self.tiles.chunks(self.width) {
+            out.extend(row.iter().map(|t| t.to_char()));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// FNV-1a от каноничного текста: не зависит от версии компилятора и платформы
+    pub fn hash(&self) -> u64 {
+        self.to_text().bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| {
+            (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+    }
+
+    pub fn tile(&self, x: usize, y: usize) -> Tile {
+        self.tiles[y * self.width + x]
+    }
+
+    /// Все клетки с координатами (x, y)
+    pub fn tiles(&self) -> impl Iterator<Item = (usize, usize, Tile)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(i, &tile)| (i % self.width, i / self.width, tile))
+    }
+
+    /// Половина размера карты в мире; (0,0) — центр карты
+    pub fn half_size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32) * TILE_SIZE * 0.5
+    }
+
+    /// Центр клетки в мировых координатах
+    pub fn tile_center(&self, x: usize, y: usize) -> Vec2 {
+        -self.half_size() + Vec2::new((x as f32 + 0.5) * TILE_SIZE, (y as f32 + 0.5) * TILE_SIZE)
+    }
+
+    /// Предметы на карте и центры их клеток; порядок — номер предмета в сети
+    pub fn pickups(&self) -> Vec<(Tile, Vec2)> {
+        self.tiles()
+            .filter(|&(_, _, tile)| tile.is_pickup())
+            .map(|(x, y, tile)| (tile, self.tile_center(x, y)))
+            .collect()
+    }
+
+    /// Стены, слитые в прямоугольники: меньше спрайтов и нет швов между клетками
+    pub fn wall_rects(&self) -> Vec<TileRect> {
+        merge_rects(self.width, self.height, |x, y| {
+            self.tile(x, y) == Tile::Wall
+        })
+    }
+
+    /// Центр прямоугольника клеток в мировых координатах
+    pub fn rect_center(&self, r: &TileRect) -> Vec2 {
+        -self.half_size() + Vec2::new(r.x as f32, r.y as f32) * TILE_SIZE + r.size() / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn err(src: &str) -> MapError {
+        GameMap::parse("test", src).unwrap_err()
+    }
+
+    #[test]
+    fn parses_rows_bottom_up() {
+        let map = GameMap::parse("test", "###\n#S#\n###\n\n\n").unwrap();
+        assert_eq!((map.width, map.height), (3, 3));
+        assert_eq!(map.tile(1, 1), Tile::Spawn);
+        assert_eq!(map.tile(0, 0), Tile::Wall);
+    }
+
+    #[test]
+    fn pickups_follow_text_order() {
+        // первая строка текста — y = 0, низ мира; внутри строки слева направо
+        let map = GameMap::parse("test", "A.H\nS.A\n").unwrap();
+        assert_eq!(
+            map.pickups(),
+            vec![
+                (Tile::ArmorVest, Vec2::new(-32.0, -16.0)),
+                (Tile::HealthPack, Vec2::new(32.0, -16.0)),
+                (Tile::ArmorVest, Vec2::new(32.0, 16.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_char_reports_line_and_column() {
+        let e = err("####\n#S.#\n#.x#\n####\n");
+        assert_eq!((e.line, e.column), (3, 3));
+        assert!(e.message.contains('x'), "{e}");
+        assert_eq!(e.to_string(), format!("строка 3, столбец 3: {}", e.message));
+    }
+
+    #[test]
+    fn ragged_rows_are_rejected() {
+        // длиннее первой — ошибка на первой лишней клетке
+        let e = err("####\n#S.##\n####\n");
+        assert_eq!((e.line, e.column), (2, 5));
+        // короче — на первой недостающей
+        let e = err("####\n#S#\n####\n");
+        assert_eq!((e.line, e.column), (2, 4));
+        // CRLF — не повод для ошибки
+        assert!(GameMap::parse("test", "###\r\n#S#\r\n###\r\n").is_ok());
+    }
+
+    #[test]
+    fn empty_map_and_map_without_spawn_are_rejected() {
+        assert_eq!((err("").line, err("").column), (1, 1));
+        assert_eq!(err("\n\n").line, 1);
+        let e = err("###\n#.#\n###\n");
+        assert!(e.message.contains("спавн"), "{e}");
+        // одних командных зон достаточно
+        assert!(GameMap::parse("test", "####\n#TC#\n####\n").is_ok());
+    }
+
+    #[test]
+    fn text_round_trips_and_hash_tracks_content() {
+        let src = "#####\r\n#S.H#\r\n#..A#\r\n#####\r\n\r\n";
+        let map = GameMap::parse("test", src).unwrap();
+        let text = map.to_text();
+        assert_eq!(text, "#####\n#S.H#\n#..A#\n#####\n");
+        assert_eq!(GameMap::parse("test", &text).unwrap(), map);
+
+        // хэш — от содержимого: тот же текст другими переводами строк и под другим именем совпадает
+        let same = GameMap::parse("copy", &text).unwrap();
+        assert_eq!(same.hash(), map.hash());
+        assert_eq!(map.hash(), map.clone().hash());
+
+        let mut changed = map.clone();
+        changed.tiles[7] = Tile::Wall;
+        assert_ne!(changed.hash(), map.hash());
+        let moved = GameMap::parse("test", "#####\n#S.H#\n#.A.#\n#####\n").unwrap();
+        assert_ne!(moved.hash(), map.hash());
+    }
+
+    #[test]
+    fn shipped_maps_parse() {
+        // только в тестах: бинарники ищут карты через maps_dir()
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets/maps");
+        let mut count = 0;
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                GameMap::load(&path).unwrap_or_else(|e| panic!("{e}"));
+                count += 1;
+            }
+        }
+        assert!(count > 0, "в {} нет карт", dir.display());
+        assert!(map_path(&dir, DEFAULT_MAP).is_file());
+    }
+
+    #[test]
+    fn wall_rects_cover_every_wall_once() {
+        let src = "\
+##########
+#S.##....#
+#..##..#.#
+#......#.#
+##########
+";
+        let map = GameMap::parse("test", src).unwrap();
+        let rects = map.wall_rects();
+        let mut covered = vec![0; map.width * map.height];
+        for r in &rects {
+            for y in r.y..r.y + r.h {
+                for x in r.x..r.x + r.w {
+                    covered[y * map.width + x] += 1;
+                }
+            }
+        }
+        for (x, y, tile) in map.tiles() {
+            let expected = if tile == Tile::Wall { 1 } else { 0 };
+            assert_eq!(covered[y * map.width + x], expected, "клетка ({x}, {y})");
+        }
+        let walls = covered.iter().filter(|&&c| c == 1).count();
+        assert!(
+            rects.len() * 3 < walls,
+            "{} прямоугольников на {walls} стен",
+            rects.len()
+        );
+    }
+}
@@ -1,22 +1,54 @@
+use crate::constants::{
+    CROUCH_HITBOX_RADIUS, CROUCH_SPEED_MULT, CROUCH_SPREAD_MULT, HITBOX_RADIUS, MOVE_SPEED,
+    PRONE_HITBOX_RADIUS, PRONE_SPEED_MULT, PRONE_SPREAD_MULT,
+};
+use crate::packed::{PackedInput, PackedSnapshot};
+use crate::weapons::{ShotSpread, WeaponKind};
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 // ----- Client → Server -----
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum C2S {
-    Input(InputState),
+    // Hello всегда первый вариант: его индекс не должен меняться между версиями
+    Hello {
+        protocol_version: u32,
+        client_name: String,
+        capabilities: u32,
+        nickname: String, // сервер проверяет длину и уникальность
+    },
+    Input(PackedInput),
     Shoot(ShootEvent),
     Heartbeat,
     Goodbye,
     Ping(f64), // отправить метку времени клиента (secs)
-    ThrowGrenade(GrenadeEvent),
+    ThrowGrenade(GrenadeThrow),
+    Reload, // начать перезарядку текущего оружия
+    SwitchWeapon(WeaponKind),
+    // эхо S2C::Pong: сервер сам меряет RTT по своей метке
+    PingEcho {
+        server_time: f64,
+    },
+    RequestMap, // локальной копии карты нет или хэш не совпал
+    // последний снапшот, который клиент собрал: от него сервер считает дельты
+    SnapshotAck {
+        tick: u32,
+    },
 }
 
 // ----- Server → Client -----
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum S2C {
-    Snapshot(WorldSnapshot),
+    // Welcome/Rejected идут первыми, чтобы их понимал клиент любой версии
+    Welcome {
+        protocol_version: u32,
+        client_id: u64,
+        capabilities: u32, // согласованные (пересечение клиентских и серверных)
+    },
+    Rejected {
+        reason: String,
+    },
+    Snapshot(PackedSnapshot),
     ShootFx(ShootFx),
     PlayerLeft(u64),
     Pong {
@@ -28,6 +60,7 @@ pub enum S2C {
     PlayerDied {
         victim: u64,
         killer: Option<u64>,
+        cause: DeathCause, // для килфида
     },
     PlayerRespawn {
         id: u64,
@@ -38,6 +71,8 @@ pub enum S2C {
         id: u64,
         x: f32,
         y: f32,
+        nickname: String,
+        spawned: bool, // false — вошёл посреди раунда, появится в следующем
     },
     PlayerDisconnected {
         id: u64,
@@ -52,6 +87,44 @@ pub enum S2C {
         pos: Vec2,
     },
     GrenadeSync { id: u64, pos: Vec2, vel: Vec2, ts: f64 }, // снапшот
+    // раунды: рассылается при смене фазы и новичкам
+    RoundState(RoundInfo),
+    PlayerTeam {
+        id: u64,
+        team: Team,
+    },
+    Scoreboard(Vec<ScoreEntry>),
+    // ник игрока: всем при входе, новичку — про остальных
+    PlayerName {
+        id: u64,
+        nickname: String,
+    },
+    // сразу после Welcome: клиент сверяет хэш с локальной копией
+    MapInfo {
+        name: String,
+        hash: u64,
+    },
+    // ответ на RequestMap: текст карты в формате assets/maps
+    MapData {
+        name: String,
+        data: String,
+    },
+    // патроны — только владельцу оружия
+    Ammo {
+        weapon: WeaponKind, // активное оружие
+        mag: u32,
+        reserve: u32,
+        reloading: bool,
+    },
+    // броня — только владельцу, при каждом изменении
+    Armor(i32),
+    // аптечка: новое HP всем (урон идёт через PlayerDamaged)
+    PlayerHealed {
+        id: u64,
+        new_hp: i32,
+    },
+    // какие предметы карты (в порядке GameMap::pickups) лежат на месте: при входе и при изменении
+    PickupStates(Vec<bool>),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -66,22 +139,29 @@ pub struct InputState {
     pub timestamp: f64,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+// стрелок не передаётся: сервер берёт его из соединения
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct ShootEvent {
-    pub shooter_id: u64,
     pub dir: Vec2,
-    pub timestamp: f64,
+    // тик, который клиент рисовал в момент выстрела, и доля пути к следующему
+    pub tick: u32,
+    pub tick_frac: f32,
+    pub seq: u32,     // номер выстрела с начала сессии, подряд с 1: сид разброса
+    pub spray: u32,   // номер выстрела в очереди, как его считает клиент
+    pub moving: bool, // клиент шёл в момент выстрела
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ShootFx {
     pub shooter_id: u64,
+    pub weapon: WeaponKind, // клиент сам разворачивает веер дроби
+    pub shot: ShotSpread,   // разброс и отдача — лучи те же, что проверял сервер
     pub from: Vec2,
     pub dir: Vec2,
     pub timestamp: f64,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum Stance {
     Standing,
     Crouching,
@@ -93,7 +173,77 @@ impl Default for Stance {
     }
 }
 
+impl Stance {
+    /// Скорость ходьбы в этой стойке
+    pub fn move_speed(&self) -> f32 {
+        MOVE_SPEED
+            * match self {
+                Stance::Standing => 1.0,
+                Stance::Crouching => CROUCH_SPEED_MULT,
+                Stance::Prone => PRONE_SPEED_MULT,
+            }
+    }
+
+    /// Радиус, в который должен пройти луч, чтобы попасть
+    pub fn hitbox_radius(&self) -> f32 {
+        match self {
+            Stance::Standing => HITBOX_RADIUS,
+            Stance::Crouching => CROUCH_HITBOX_RADIUS,
+            Stance::Prone => PRONE_HITBOX_RADIUS,
+        }
+    }
+
+    /// Во сколько раз сужается разброс оружия
+    pub fn spread_mult(&self) -> f32 {
+        match self {
+            Stance::Standing => 1.0,
+            Stance::Crouching => CROUCH_SPREAD_MULT,
+            Stance::Prone => PRONE_SPREAD_MULT,
+        }
+    }
+}
+
+// Чем убили
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    Weapon(WeaponKind),
+    Grenade,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Team {
+    Terrorists,
+    CounterTerrorists,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundPhase {
+    Warmup, // ждём игроков в обеих командах, респавн включён
+    Freeze, // стоим, не стреляем
+    Live,
+    Ended { winner: Option<Team> }, // None — ничья
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RoundInfo {
+    pub phase: RoundPhase,
+    pub round: u32,
+    pub time_left: f32, // до конца фазы, secs
+    pub score_t: u32,
+    pub score_ct: u32,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScoreEntry {
+    pub id: u64,
+    pub team: Option<Team>, // None — дезматч
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+    pub ping_ms: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct PlayerSnapshot {
     pub id: u64,
     pub x: f32,
@@ -103,11 +253,43 @@ pub struct PlayerSnapshot {
     pub hp: i32,
 }
 
+// Изменённые поля игрока относительно базового снапшота; None — не изменилось
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PlayerDelta {
+    pub id: u64,
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+    pub rotation: Option<f32>,
+    pub stance: Option<Stance>,
+    pub hp: Option<i32>,
+}
+
+// Снапшот по проводу: дельта к подтверждённому клиентом тику или полный (baseline: None)
 #[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SnapshotDelta {
+    pub tick: u32,
+    pub baseline: Option<u32>,
+    pub server_time: f64,
+    pub changed: Vec<PlayerDelta>,
+    pub removed: Vec<u64>,
+    pub ack_seq: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct WorldSnapshot {
     pub players: Vec<PlayerSnapshot>,
+    pub tick: u32, // номер тика симуляции сервера
     pub server_time: f64,
-    pub last_input_seq: HashMap<u64, u32>,
+    pub ack_seq: u32, // последний применённый инпут получателя (0 — ещё ни одного)
+}
+
+// Запрос на бросок: id, точку вылета и владельца назначает сервер
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GrenadeThrow {
+    pub dir: Vec2,
+    pub speed: f32, // желаемая, сервер ограничивает GRENADE_SPEED
+    pub timer: f32, // желаемая, сервер ограничивает GRENADE_TIMER
+    pub timestamp: f64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -0,0 +1,471 @@
+//! Битовая упаковка горячих сообщений: снапшотов (каждый тик каждому) и инпутов (каждый тик от каждого)
+use crate::messages::{InputState, PlayerDelta, SnapshotDelta, Stance};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, TAU};
+
+const POS_BITS: u32 = 16; // карта 50 клеток → шаг ~0.024px
+const ROT_BITS: u32 = 16;
+const STANCE_BITS: u32 = 2;
+const HP_BITS: u32 = 7; // 0..=127, хватает на 100
+const COUNT_BITS: u32 = 10; // игроков в одном снапшоте, не больше MAX_COUNT
+const VAR_LEN_BITS: u32 = 7; // длина значения в битах, 0..=64
+const MAX_COUNT: usize = (1 << COUNT_BITS) - 1;
+
+/// `SnapshotDelta` в битовой упаковке; позиции квантованы относительно границ карты
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PackedSnapshot(pub Vec<u8>);
+
+/// `InputState` в битовой упаковке; кнопки — битовое поле
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PackedInput(pub Vec<u8>);
+
+impl PackedSnapshot {
+    /// `half_size` — половина размера карты (`GameMap::half_size`), у сервера и клиента одна.
+    /// None — игроков или ушедших больше MAX_COUNT: обрезанный счётчик сбил бы разбор пакета
+    pub fn pack(delta: &SnapshotDelta, half_size: Vec2) -> Option<Self> {
+        if delta.changed.len() > MAX_COUNT || delta.removed.len() > MAX_COUNT {
+            return None;
+        }
+        let mut w = BitWriter::default();
+        w.write(delta.tick as u64, 32);
+        w.write_bool(delta.baseline.is_some());
+        if let Some(base) = delta.baseline {
+            w.write(base as u64, 32);
+        }
+        w.write(delta.server_time.to_bits(), 64);
+        w.write(delta.ack_seq as u64, 32);
+
+        w.write(delta.changed.len() as u64, COUNT_BITS);
+        for d in &delta.changed {
+            w.write_var(d.id);
+            for present in [
+                d.x.is_some(),
+                d.y.is_some(),
+                d.rotation.is_some(),
+                d.stance.is_some(),
+                d.hp.is_some(),
+            ] {
+                w.write_bool(present);
+            }
+            if let Some(x) = d.x {
+                w.write(quantize(x, half_size.x), POS_BITS);
+            }
+            if let Some(y) = d.y {
+                w.write(quantize(y, half_size.y), POS_BITS);
+            }
+            if let Some(rot) = d.rotation {
+                w.write(quantize_angle(rot), ROT_BITS);
+            }
+            if let Some(stance) = &d.stance {
+                w.write(stance_bits(stance), STANCE_BITS);
+            }
+            if let Some(hp) = d.hp {
+                w.write(hp.clamp(0, (1 << HP_BITS) - 1) as u64, HP_BITS);
+            }
+        }
+
+        w.write(delta.removed.len() as u64, COUNT_BITS);
+        for &id in &delta.removed {
+            w.write_var(id);
+        }
+        Some(Self(w.finish()))
+    }
+
+    /// None — пакет обрезан или испорчен
+    pub fn unpack(&self, half_size: Vec2) -> Option<SnapshotDelta> {
+        let mut r = BitReader::new(&self.0);
+        let tick = r.read(32)? as u32;
+        let baseline = if r.read_bool()? {
+            Some(r.read(32)? as u32)
+        } else {
+            None
+        };
+        let server_time = f64::from_bits(r.read(64)?);
+        let ack_seq = r.read(32)? as u32;
+
+        let count = r.read(COUNT_BITS)? as usize;
+        let mut changed = Vec::with_capacity(count);
+        for _ in 0..count {
+            let id = r.read_var()?;
+            let mut present = [false; 5];
+            for p in &mut present {
+                *p = r.read_bool()?;
+            }
+            let [has_x, has_y, has_rot, has_stance, has_hp] = present;
+            let mut d = PlayerDelta {
+                id,
+                ..Default::default()
+            };
+            if has_x {
+                d.x = Some(dequantize(r.read(POS_BITS)?, half_size.x));
+            }
+            if has_y {
+                d.y = Some(dequantize(r.read(POS_BITS)?, half_size.y));
+            }
+            if has_rot {
+                d.rotation = Some(dequantize_angle(r.read(ROT_BITS)?));
+            }
+            if has_stance {
+                d.stance = Some(stance_from_bits(r.read(STANCE_BITS)?)?);
+            }
+            if has_hp {
+                d.hp = Some(r.read(HP_BITS)? as i32);
+            }
+            changed.push(d);
+        }
+
+        let count = r.read(COUNT_BITS)? as usize;
+        let mut removed = Vec::with_capacity(count);
+        for _ in 0..count {
+            removed.push(r.read_var()?);
+        }
+
+        Some(SnapshotDelta {
+            tick,
+            baseline,
+            server_time,
+            changed,
+            removed,
+            ack_seq,
+        })
+    }
+}
+
+impl PackedInput {
+    pub fn pack(input: &InputState) -> Self {
+        let mut w = BitWriter::default();
+        w.write(input.seq as u64, 32);
+        for pressed in [input.up, input.down, input.left, input.right] {
+            w.write_bool(pressed);
+        }
+        w.write(quantize_angle(input.rotation), ROT_BITS);
+        w.write(stance_bits(&input.stance), STANCE_BITS);
+        w.write(input.timestamp.to_bits(), 64);
+        Self(w.finish())
+    }
+
+    pub fn unpack(&self) -> Option<InputState> {
+        let mut r = BitReader::new(&self.0);
+        Some(InputState {
+            seq: r.read(32)? as u32,
+            up: r.read_bool()?,
+            down: r.read_bool()?,
+            left: r.read_bool()?,
+            right: r.read_bool()?,
+            rotation: dequantize_angle(r.read(ROT_BITS)?),
+            stance: stance_from_bits(r.read(STANCE_BITS)?)?,
+            timestamp: f64::from_bits(r.read(64)?),
+        })
+    }
+}
+
+// ---- квантование -----------------------------------------------------------
+
+/// [-half, half] → [0, 2^POS_BITS - 1]; за границами карты прижимаем к краю
+fn quantize(v: f32, half: f32) -> u64 {
+    let max = ((1u64 << POS_BITS) - 1) as f32;
+    let t = ((v + half) / (2.0 * half)).clamp(0.0, 1.0);
+    (t * max).round() as u64
+}
+
+fn dequantize(q: u64, half: f32) -> f32 {
+    let max = ((1u64 << POS_BITS) - 1) as f32;
+    q as f32 / max * 2.0 * half - half
+}
+
+/// Угол по модулю полного оборота; обратно — в (-PI, PI], как у atan2
+fn quantize_angle(rot: f32) -> u64 {
+    let steps = (1u64 << ROT_BITS) as f32;
+    (rot.rem_euclid(TAU) / TAU * steps).round() as u64 % (1 << ROT_BITS)
+}
+
+fn dequantize_angle(q: u64) -> f32 {
+    let rot = q as f32 / (1u64 << ROT_BITS) as f32 * TAU;
+    if rot > PI { rot - TAU } else { rot }
+}
+
+fn stance_bits(stance: &Stance) -> u64 {
+    match stance {
+        Stance::Standing => 0,
+        Stance::Crouching => 1,
+        Stance::Prone => 2,
+    }
+}
+
+fn stance_from_bits(bits: u64) -> Option<Stance> {
+    match bits {
+        0 => Some(Stance::Standing),
+        1 => Some(Stance::Crouching),
+        2 => Some(Stance::Prone),
+        _ => None,
+    }
+}
+
+// ---- битовый поток ---------------------------------------------------------
+
+/// Пишем младшими битами вперёд
+#[derive(Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    bits: usize,
+}
+
+impl BitWriter {
+    /// Младшие `bits` бит значения
+    pub fn write(&mut self, value: u64, bits: u32) {
+        for i in 0..bits {
+            if self.bits.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if (value >> i) & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 1 << (self.bits % 8);
+            }
+            self.bits += 1;
+        }
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write(value as u64, 1);
+    }
+
+    /// Длина в битах, затем значащие биты: маленькие id занимают пару байт вместо восьми
+    pub fn write_var(&mut self, value: u64) {
+        let len = u64::BITS - value.leading_zeros();
+        self.write(len as u64, VAR_LEN_BITS);
+        self.write(value, len);
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    bits: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, bits: 0 }
+    }
+
+    /// None — поток кончился
+    pub fn read(&mut self, bits: u32) -> Option<u64> {
+        let mut value = 0;
+        for i in 0..bits {
+            let byte = *self.bytes.get(self.bits / 8)?;
+            if (byte >> (self.bits % 8)) & 1 == 1 {
+                value |= 1 << i;
+            }
+            self.bits += 1;
+        }
+        Some(value)
+    }
+
+    pub fn read_bool(&mut self) -> Option<bool> {
+        Some(self.read(1)? == 1)
+    }
+
+    pub fn read_var(&mut self) -> Option<u64> {
+        let len = self.read(VAR_LEN_BITS)? as u32;
+        if len > u64::BITS {
+            return None;
+        }
+        self.read(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HALF: Vec2 = Vec2::new(640.0, 480.0);
+
+    fn pos_step(half: f32) -> f32 {
+        2.0 * half / ((1u64 << POS_BITS) - 1) as f32
+    }
+
+    fn rot_step() -> f32 {
+        TAU / (1u64 << ROT_BITS) as f32
+    }
+
+    fn round_trip(delta: &SnapshotDelta) -> SnapshotDelta {
+        PackedSnapshot::pack(delta, HALF)
+            .unwrap()
+            .unpack(HALF)
+            .unwrap()
+    }
+
+    fn sample_delta() -> SnapshotDelta {
+        SnapshotDelta {
+            tick: 123_456,
+            baseline: Some(123_400),
+            server_time: 1929.015625,
+            changed: vec![
+                PlayerDelta {
+                    id: 7,
+                    x: Some(-123.45),
+                    y: Some(400.5),
+                    rotation: Some(-2.9),
+                    stance: Some(Stance::Prone),
+                    hp: Some(100),
+                },
+                // только то, что поменялось
+                PlayerDelta {
+                    id: u64::MAX,
+                    rotation: Some(PI),
+                    ..Default::default()
+                },
+                PlayerDelta {
+                    id: 0,
+                    hp: Some(0),
+                    ..Default::default()
+                },
+            ],
+            removed: vec![3, 1 << 40],
+            ack_seq: 98_765,
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let delta = sample_delta();
+        let back = round_trip(&delta);
+
+        assert_eq!(back.tick, delta.tick);
+        assert_eq!(back.baseline, delta.baseline);
+        assert_eq!(back.server_time, delta.server_time);
+        assert_eq!(back.ack_seq, delta.ack_seq);
+        assert_eq!(back.removed, delta.removed);
+        assert_eq!(back.changed.len(), delta.changed.len());
+        for (a, b) in delta.changed.iter().zip(&back.changed) {
+            assert_eq!(a.id, b.id);
+            assert_eq!(a.stance, b.stance);
+            assert_eq!(a.hp, b.hp);
+            assert_eq!(a.x.is_some(), b.x.is_some());
+            assert_eq!(a.y.is_some(), b.y.is_some());
+            assert_eq!(a.rotation.is_some(), b.rotation.is_some());
+            if let (Some(x), Some(bx)) = (a.x, b.x) {
+                assert!((x - bx).abs() <= pos_step(HALF.x));
+            }
+            if let (Some(y), Some(by)) = (a.y, b.y) {
+                assert!((y - by).abs() <= pos_step(HALF.y));
+            }
+            if let (Some(r), Some(br)) = (a.rotation, b.rotation) {
+                assert!((r - br).abs() <= rot_step());
+            }
+        }
+    }
+
+    #[test]
+    fn full_snapshot_round_trip() {
+        let delta = SnapshotDelta {
+            baseline: None,
+            removed: Vec::new(),
+            ..sample_delta()
+        };
+        let back = round_trip(&delta);
+        assert_eq!(back.baseline, None);
+        assert!(back.removed.is_empty());
+        assert_eq!(back.changed.len(), 3);
+    }
+
+    #[test]
+    fn too_many_players_are_refused() {
+        let players = |n: u64| {
+            (0..n)
+                .map(|id| PlayerDelta {
+                    id,
+                    ..Default::default()
+                })
+                .collect()
+        };
+        let fits = SnapshotDelta {
+            changed: players(MAX_COUNT as u64),
+            ..sample_delta()
+        };
+        let back = round_trip(&fits);
+        assert_eq!(back.changed.len(), MAX_COUNT);
+        assert_eq!(back.removed, fits.removed);
+
+        let over = SnapshotDelta {
+            changed: players(MAX_COUNT as u64 + 1),
+            ..sample_delta()
+        };
+        assert!(PackedSnapshot::pack(&over, HALF).is_none());
+        let over = SnapshotDelta {
+            removed: (0..=MAX_COUNT as u64).collect(),
+            ..sample_delta()
+        };
+        assert!(PackedSnapshot::pack(&over, HALF).is_none());
+    }
+
+    #[test]
+    fn input_round_trip() {
+        for (up, down, left, right) in [
+            (true, false, false, true),
+            (false, true, true, false),
+            (false, false, false, false),
+        ] {
+            let input = InputState {
+                seq: 4_000_000_000,
+                up,
+                down,
+                left,
+                right,
+                rotation: 1.234,
+                stance: Stance::Crouching,
+                timestamp: 1_700_000_000.123,
+            };
+            let back = PackedInput::pack(&input).unpack().unwrap();
+            assert_eq!(back.seq, input.seq);
+            assert_eq!(
+                (back.up, back.down, back.left, back.right),
+                (up, down, left, right)
+            );
+            assert_eq!(back.stance, input.stance);
+            assert_eq!(back.timestamp, input.timestamp);
+            assert!((back.rotation - input.rotation).abs() <= rot_step());
+        }
+    }
+
+    #[test]
+    fn positions_outside_map_are_clamped() {
+        assert_eq!(dequantize(quantize(HALF.x * 3.0, HALF.x), HALF.x), HALF.x);
+        assert_eq!(dequantize(quantize(-HALF.x * 3.0, HALF.x), HALF.x), -HALF.x);
+    }
+
+    #[test]
+    fn angles_wrap_into_atan2_range() {
+        for rot in [-PI, -3.0, -0.001, 0.0, 0.001, 3.0, PI, 7.0, -7.0] {
+            let back = dequantize_angle(quantize_angle(rot));
+            assert!(back > -PI - 1e-6 && back <= PI);
+            // сравниваем по модулю оборота
+            let diff = (back - rot).rem_euclid(TAU);
+            assert!(diff.min(TAU - diff) <= rot_step());
+        }
+    }
+
+    #[test]
+    fn truncated_packet_is_rejected() {
+        let packed = PackedSnapshot::pack(&sample_delta(), HALF).unwrap();
+        for len in 0..packed.0.len() {
+            let cut = PackedSnapshot(packed.0[..len].to_vec());
+            assert!(cut.unpack(HALF).is_none(), "обрезка до {len} байт");
+        }
+        let input = PackedInput::pack(&InputState {
+            seq: 1,
+            up: true,
+            down: false,
+            left: false,
+            right: false,
+            rotation: 0.0,
+            stance: Stance::Standing,
+            timestamp: 0.0,
+        });
+        let cut = PackedInput(input.0[..input.0.len() - 1].to_vec());
+        assert!(cut.unpack().is_none());
+    }
+}
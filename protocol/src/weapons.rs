@@ -0,0 +1,228 @@
+use crate::constants::SPRAY_RESET_SECS;
+use crate::messages::Stance;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+// увод прицела от отдачи по номеру выстрела в очереди, в долях `WeaponDef::recoil`;
+// дальше последнего значения не растёт
+const RECOIL_PATTERN: [f32; 10] = [0.0, 1.0, 2.0, 2.8, 3.4, 3.6, 3.2, 3.8, 4.2, 3.9];
+
+// Виды оружия; порядок = слоты 1..4 на клавиатуре
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum WeaponKind {
+    Pistol,
+    #[default]
+    Rifle,
+    Shotgun,
+    Sniper,
+}
+
+impl WeaponKind {
+    pub const ALL: [WeaponKind; 4] = [
+        WeaponKind::Pistol,
+        WeaponKind::Rifle,
+        WeaponKind::Shotgun,
+        WeaponKind::Sniper,
+    ];
+
+    pub fn def(self) -> &'static WeaponDef {
+        &WEAPONS[self as usize]
+    }
+}
+
+/// Параметры оружия — общие для клиента и сервера
+#[derive(Debug)]
+pub struct WeaponDef {
+    pub name: &'static str,
+    pub damage: f32,          // за одну пулю/дробину
+    pub range: f32,           // дальность хитскана
+    pub spread: f32,          // ширина веера дроби, рад
+    pub inaccuracy: f32,      // полуугол конуса разброса первого выстрела на месте, рад
+    pub move_inaccuracy: f32, // добавка к конусу на ходу
+    pub bloom: f32,           // добавка к конусу за каждый выстрел очереди
+    pub recoil: f32,          // шаг отдачи, рад (см. RECOIL_PATTERN)
+    pub fire_interval: f64,   // secs между выстрелами
+    pub pellets: u32,         // лучей за выстрел
+    pub mag_size: u32,
+    pub reserve: u32, // запас при спавне
+    pub reload_time: f64,
+}
+
+// индексы совпадают с порядком WeaponKind
+pub const WEAPONS: [WeaponDef; 4] = [
+    WeaponDef {
+        name: "Пистолет",
+        damage: 15.0,
+        range: 1500.0,
+        spread: 0.0,
+        inaccuracy: 0.01,
+        move_inaccuracy: 0.05,
+        bloom: 0.01,
+        recoil: 0.02,
+        fire_interval: 0.15,
+        pellets: 1,
+        mag_size: 12,
+        reserve: 36,
+        reload_time: 1.8,
+    },
+    WeaponDef {
+        name: "Винтовка",
+        damage: 20.0,
+        range: 3000.0,
+        spread: 0.0,
+        inaccuracy: 0.005,
+        move_inaccuracy: 0.08,
+        bloom: 0.008,
+        recoil: 0.015,
+        fire_interval: 0.1, // 600 в минуту
+        pellets: 1,
+        mag_size: 30,
+        reserve: 90,
+        reload_time: 2.5,
+    },
+    WeaponDef {
+        name: "Дробовик",
+        damage: 12.0,
+        range: 600.0,
+        spread: 0.35, // ~20°
+        inaccuracy: 0.02,
+        move_inaccuracy: 0.04,
+        bloom: 0.0,
+        recoil: 0.05,
+        fire_interval: 0.9,
+        pellets: 8,
+        mag_size: 8,
+        reserve: 32,
+        reload_time: 3.0,
+    },
+    WeaponDef {
+        name: "Снайперка",
+        damage: 90.0,
+        range: 5000.0,
+        spread: 0.0,
+        inaccuracy: 0.0, // с места — точно в прицел
+        move_inaccuracy: 0.15,
+        bloom: 0.0,
+        recoil: 0.0,
+        fire_interval: 1.25,
+        pellets: 1,
+        mag_size: 5,
+        reserve: 20,
+        reload_time: 3.5,
+    },
+];
+
+/// Всё, от чего зависят разброс и отдача выстрела: по нему клиент и сервер получают одни лучи
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ShotSpread {
+    pub seq: u32,   // номер выстрела клиента — сид разброса
+    pub spray: u32, // номер выстрела в очереди, 0 — первый
+    pub moving: bool,
+    pub stance: Stance,
+}
+
+/// Номер следующего выстрела в очереди: после долгой паузы очередь начинается заново
+pub fn next_spray(prev: u32, since_last_shot: f64, def: &WeaponDef) -> u32 {
+    if since_last_shot > def.fire_interval + SPRAY_RESET_SECS {
+        0
+    } else {
+        prev.saturating_add(1)
+    }
+}
+
+/// Число в [-1, 1) из номера выстрела (splitmix64): одинаково на любой платформе
+fn shot_noise(seq: u32) -> f32 {
+    let mut z = (seq as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 40) as f32 / (1u32 << 23) as f32 - 1.0
+}
+
+/// Направления лучей выстрела. Центр уводит отдача и случайный сдвиг в конусе разброса
+/// (шире на ходу и в очереди, уже присев и лёжа); дробь — равномерный веер шириной
+/// `spread` вокруг центра
+pub fn pellet_dirs(dir: Vec2, def: &WeaponDef, shot: &ShotSpread) -> Vec<Vec2> {
+    let dir = dir.normalize_or_zero();
+    let mult = shot.stance.spread_mult();
+    let spray = (shot.spray as usize).min(RECOIL_PATTERN.len() - 1);
+    let mut cone = def.inaccuracy + def.bloom * spray as f32;
+    if shot.moving {
+        cone += def.move_inaccuracy;
+    }
+    let offset = def.recoil * RECOIL_PATTERN[spray] + cone * mult * shot_noise(shot.seq);
+    let center = Vec2::from_angle(offset).rotate(dir);
+    if def.pellets <= 1 {
+        return vec![center];
+    }
+    let spread = def.spread * mult;
+    let step = spread / (def.pellets - 1) as f32;
+    (0..def.pellets)
+        .map(|i| Vec2::from_angle(-spread * 0.5 + step * i as f32).rotate(center))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn angle(shot: &ShotSpread) -> f32 {
+        let dirs = pellet_dirs(Vec2::X, WeaponKind::Rifle.def(), shot);
+        dirs[0].to_angle()
+    }
+
+    // самый большой угол по 200 выстрелам подряд
+    fn widest(moving: bool, stance: Stance) -> f32 {
+        (1..200)
+            .map(|seq| {
+                let shot = ShotSpread {
+                    seq,
+                    moving,
+                    stance: stance.clone(),
+                    ..Default::default()
+                };
+                angle(&shot).abs()
+            })
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn spread_is_deterministic_and_grows() {
+        let shot = |seq| ShotSpread {
+            seq,
+            ..Default::default()
+        };
+        assert_eq!(angle(&shot(7)), angle(&shot(7)));
+        // разные выстрелы ложатся по-разному
+        assert_ne!(angle(&shot(7)), angle(&shot(8)));
+
+        let rifle = WeaponKind::Rifle.def();
+        let standing = widest(false, Stance::Standing);
+        assert!(standing <= rifle.inaccuracy + 1e-6);
+        // на ходу конус шире, лёжа — уже
+        let moving = widest(true, Stance::Standing);
+        assert!(moving > standing * 5.0);
+        assert!(widest(true, Stance::Prone) < moving);
+    }
+
+    #[test]
+    fn recoil_climbs_during_spray_and_resets_after_pause() {
+        let rifle = WeaponKind::Rifle.def();
+        let mut spray = 0;
+        for _ in 0..4 {
+            spray = next_spray(spray, rifle.fire_interval, rifle);
+        }
+        assert_eq!(spray, 4);
+        // центр уведён отдачей, конус расширен очередью
+        let fifth = angle(&ShotSpread {
+            spray,
+            ..Default::default()
+        });
+        let cone = rifle.inaccuracy + rifle.bloom * 4.0;
+        let expected = rifle.recoil * RECOIL_PATTERN[4] + cone * shot_noise(0);
+        assert!((fifth - expected).abs() < 1e-5);
+        assert!(fifth > angle(&ShotSpread::default()));
+
+        assert_eq!(next_spray(spray, 1.0, rifle), 0);
+    }
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use protocol::constants::TICK_DT;
+use protocol::map::DEFAULT_MAP;
+use std::path::PathBuf;
+
+/// Больше не даём: история сервера хранит 120 тиков (~1.8 с)
+const MAX_REWIND_LIMIT_MS: u32 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameMode {
+    Deathmatch, // все против всех, мгновенный респавн
+    Rounds,     // T против CT, респавн только в новом раунде
+}
+
+/// Параметры запуска сервера (аргументы командной строки)
+#[derive(Resource, Clone, Debug)]
+pub struct ServerConfig {
+    pub mode: GameMode,
+    pub map: String,               // имя из assets/maps или путь к .txt
+    pub maps_dir: Option<PathBuf>, // где искать карты по имени; None — protocol::map::maps_dir()
+    pub max_rewind_ms: u32,        // насколько назад лаг-компенсация откатывает выстрел
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::Deathmatch,
+            map: DEFAULT_MAP.to_string(),
+            maps_dir: None,
+            max_rewind_ms: 200,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// `server [--mode dm|rounds] [--map <имя|путь.txt>] [--maps-dir <каталог>] [--max-rewind-ms <мс>]`
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut cfg = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--mode" => {
+                    let value = args.next().ok_or("--mode: нужно значение (dm|rounds)")?;
+                    cfg.mode = match value.as_str() {
+                        "dm" | "deathmatch" => GameMode::Deathmatch,
+                        "rounds" => GameMode::Rounds,
+                        other => return Err(format!("--mode: неизвестный режим '{other}'")),
+                    };
+                }
+                "--map" => {
+                    cfg.map = args
+                        .next()
+                        .ok_or("--map: нужно имя карты или путь к .txt")?;
+                }
+                "--maps-dir" => {
+                    let dir = args.next().ok_or("--maps-dir: нужен путь к каталогу")?;
+                    cfg.maps_dir = Some(PathBuf::from(dir));
+                }
+                "--max-rewind-ms" => {
+                    let value = args.next().ok_or("--max-rewind-ms: нужно значение в мс")?;
+                    cfg.max_rewind_ms = value
+                        .parse()
+                        .ok()
+                        .filter(|&ms| ms <= MAX_REWIND_LIMIT_MS)
+                        .ok_or(format!(
+                            "--max-rewind-ms: ожидается 0..={MAX_REWIND_LIMIT_MS}, получено '{value}'"
+                        ))?;
+                }
+                other => return Err(format!("неизвестный аргумент '{other}'")),
+            }
+        }
+        Ok(cfg)
+    }
+
+    /// Предел отката в тиках сервера
+    pub fn max_rewind_ticks(&self) -> u32 {
+        (self.max_rewind_ms as f32 / 1000.0 / TICK_DT).round() as u32
+    }
+}
+
+/// run_if для систем режима раундов
+pub fn rounds_mode(config: Res<ServerConfig>) -> bool {
+    config.mode == GameMode::Rounds
+}
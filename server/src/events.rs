@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use protocol::messages::DeathCause;
 
 /// Событие урона: любой источник пишет сюда
 #[derive(Event)]
@@ -6,6 +7,7 @@ pub struct DamageEvent {
     pub target: u64,
     pub amount: i32,
     pub source: Option<u64>,
+    pub cause: DeathCause,
 }
 
 #[derive(Event)]
@@ -14,6 +16,10 @@ pub struct ClientConnected(pub u64);
 #[derive(Event)]
 pub struct ClientDisconnected(pub u64);
 
+// Клиент прошёл рукопожатие (Hello → Welcome) и может входить в игру
+#[derive(Event)]
+pub struct PlayerJoined(pub u64);
+
 // Дискретное событие «игрок должен появиться»
 #[derive(Event)]
 pub struct PlayerRespawn {
@@ -1,4 +1,5 @@
 // todo solute this!
+mod config;
 mod constants;
 mod events;
 mod net;
@@ -13,16 +14,20 @@ use bevy::{
 };
 use bevy_quinnet::server::{ConnectionEvent, ConnectionLostEvent, QuinnetServerPlugin};
 
+use config::{ServerConfig, rounds_mode};
 use constants::*;
 use events::*;
+use protocol::map::{GameMap, map_path, maps_dir};
 use resources::*;
 use systems::{
-    connection::*, damage::*, process_c2s::*, respawn_timers::*, server_tick::*, spawn::*,
-    startup::*, timeout::*, update_grenades::*,
+    connection::*, damage::*, process_c2s::*, respawn_timers::*, rounds::update_rounds,
+    scoreboard::broadcast_scoreboard, server_tick::*, spawn::*, startup::*, timeout::*,
+    update_grenades::*, weapons::*,
 };
 
 use crate::systems::{
-    level_fixed::setup_fixed_level, spawn::process_player_respawn, wall::spawn_level_server,
+    level_map::setup_map_level, pickups::update_pickups, spawn::process_player_respawn,
+    wall::spawn_level_server,
 };
 // use systems::{
 //     connection::{handle_disconnections, handle_new_connections},
@@ -43,26 +48,54 @@ fn main() {
     })
     .expect("Error setting Ctrl‑C handler");
 
+    let config = ServerConfig::from_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("❌ {err}\nUsage: server [--mode dm|rounds] [--map <name|path.txt>] [--maps-dir <dir>] [--max-rewind-ms <ms>]");
+        std::process::exit(2);
+    });
+    println!("🎮 Режим: {:?}", config.mode);
+    let dir = config.maps_dir.clone().unwrap_or_else(maps_dir);
+    let map = GameMap::load(&map_path(&dir, &config.map)).unwrap_or_else(|err| {
+        eprintln!("❌ Карта: {err}");
+        std::process::exit(2);
+    });
+
     App::new()
-        .insert_resource(ServerTickTimer(Timer::from_seconds(
-            TICK_DT,
-            TimerMode::Repeating,
-        )))
+        .insert_resource(config)
+        .insert_resource(ActiveMap(map))
+        .insert_resource(Time::<Fixed>::from_seconds(TICK_DT as f64))
+        .insert_resource(ServerTick::default())
         .insert_resource(PlayerStates::default())
         .insert_resource(PendingInputs::default())
         .insert_resource(AppliedSeqs::default())
+        .insert_resource(SnapshotAcks::default())
+        .insert_resource(SentSnapshots::default())
+        .insert_resource(ShotSeqs::default())
         .insert_resource(LastHeard::default())
         .insert_resource(SnapshotHistory::default())
         .insert_resource(Grenades::default())
+        .insert_resource(NextGrenadeId::default())
         .insert_resource(RespawnQueue::default())
         .insert_resource(RespawnDelay::default())
         .insert_resource(ConnectedClients::default())
         .insert_resource(SpawnedClients::default())
+        .insert_resource(HandshakedClients::default())
+        .insert_resource(Nicknames::default())
         .insert_resource(LastGrenadeThrows::default())
+        .insert_resource(WeaponStates::default())
+        .insert_resource(Teams::default())
+        .insert_resource(TeamSpawns::default())
+        .insert_resource(RoundState::default())
+        .insert_resource(Scores::default())
+        .insert_resource(DamageLog::default())
+        .insert_resource(Latencies::default())
+        .insert_resource(ScoreboardTimer(Timer::from_seconds(
+            SCOREBOARD_INTERVAL,
+            TimerMode::Repeating,
+        )))
         .insert_resource(GrenadeSyncTimer(Timer::from_seconds(
-            0.1,
+            GRENADE_SYNC_INTERVAL,
             TimerMode::Repeating,
-        ))) // 10 Гц
+        )))
         .add_plugins((
             MinimalPlugins, // базовый набор
             LogPlugin {
@@ -78,23 +111,31 @@ fn main() {
         .add_event::<DamageEvent>()
         .add_event::<ClientConnected>()
         .add_event::<ClientDisconnected>()
+        .add_event::<PlayerJoined>()
         .add_event::<PlayerRespawn>()
-        .add_systems(Startup, (start_server, setup_fixed_level).chain()) // spawn_level_server
+        .add_systems(Startup, (start_server, setup_map_level).chain()) // spawn_level_server
         .add_systems(PreUpdate, (handle_new_connections, handle_disconnections))
+        // авторитетная симуляция — строго раз в TICK_DT, сколько бы кадров ни прошло
+        .add_systems(
+            FixedUpdate,
+            (server_tick, update_grenades, update_pickups).chain(),
+        )
         .add_systems(
             Update,
             (
                 // todo !!!! сделать через ивент handler_disconnections !!!!!!
                 drop_inactive,        // 1. вырубаем «молчунов»
                 process_c2s_messages, // 2. обрабатываем входы (+ Heartbeat/Goodbye)
-                server_tick,          // 3. рассылаем снапшот
                 process_client_connected,
+                process_player_joined,
                 process_client_disconnected,
                 process_player_respawn,
                 process_respawn_timers,
                 apply_damage,
-                update_grenades,
+                finish_reloads,
+                update_rounds.run_if(rounds_mode),
                 broadcast_grenade_syncs,
+                broadcast_scoreboard,
                 // handle_player_died,
                 // do_respawn,
                 // purge_deaths, // todo revert???
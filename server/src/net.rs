@@ -8,6 +8,7 @@ pub fn channels_config() -> ChannelsConfiguration {
             ChannelKind::OrderedReliable { max_frame_size },
         Reliability::UnorderedReliable { max_frame_size } =>
             ChannelKind::UnorderedReliable { max_frame_size },
+        Reliability::Unreliable => ChannelKind::Unreliable,
     }).collect::<Vec<_>>();
 
     ChannelsConfiguration::from_types(kinds).expect("invalid channel config")
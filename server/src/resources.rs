@@ -3,8 +3,10 @@ use bevy::{
     prelude::{Resource, Timer},
 };
 use protocol::{
-    constants::RESPAWN_COOLDOWN,
-    messages::{GrenadeEvent, InputState},
+    constants::{FIRE_JITTER_SECS, RESPAWN_COOLDOWN},
+    map::{GameMap, Tile},
+    messages::{GrenadeEvent, InputState, RoundInfo, RoundPhase, S2C, Team, WorldSnapshot},
+    weapons::{WeaponDef, WeaponKind, next_spray},
 };
 use std::collections::{HashMap, HashSet, VecDeque};
 
@@ -14,17 +16,20 @@ pub struct PlayerState {
     pub rot: f32,
     pub stance: protocol::messages::Stance,
     pub hp: i32,
+    pub armor: i32,
 }
 
 #[derive(Resource, Default)]
 pub struct PlayerStates(pub HashMap<u64, PlayerState>);
 
-#[derive(Resource)]
-pub struct ServerTickTimer(pub Timer);
+// Номер тика симуляции: +1 в каждом FixedUpdate
+#[derive(Resource, Default)]
+pub struct ServerTick(pub u32);
 
+// История состояний по тикам для лаг-компенсации
 #[derive(Resource)]
 pub struct SnapshotHistory {
-    pub buf: VecDeque<(f64, HashMap<u64, PlayerState>)>,
+    pub buf: VecDeque<(u32, HashMap<u64, PlayerState>)>,
     pub cap: usize,
 }
 impl Default for SnapshotHistory {
@@ -42,19 +47,36 @@ pub struct PendingInputs(pub HashMap<u64, VecDeque<InputState>>);
 #[derive(Resource, Default)]
 pub struct AppliedSeqs(pub HashMap<u64, u32>);
 
+// Последний тик снапшота, который подтвердил клиент — база для дельт
+#[derive(Resource, Default)]
+pub struct SnapshotAcks(pub HashMap<u64, u32>);
+
+// Что ушло каждому клиенту, начиная с подтверждённого тика: у каждого свой набор видимых игроков
+#[derive(Resource, Default)]
+pub struct SentSnapshots(pub HashMap<u64, VecDeque<WorldSnapshot>>);
+
+// Номер последнего выстрела клиента: из него сид разброса, поэтому номера идут строго подряд
+#[derive(Resource, Default)]
+pub struct ShotSeqs(pub HashMap<u64, u32>);
+
 #[derive(Resource, Default)]
 pub struct LastHeard(pub HashMap<u64, f64>); // client_id → time (secs)
 
 pub struct GrenadeState {
     pub ev: GrenadeEvent,
+    pub owner: u64, // кто бросил — ему засчитываются убийства
     pub created: f64,
-    pub pos: Vec2,
-    pub vel: Vec2,
+    pub body: sim::GrenadeBody,
+    pub ticks: u32, // сколько шагов TICK_DT просчитано от created
 }
 
 #[derive(Resource, Default)]
 pub struct Grenades(pub HashMap<u64, GrenadeState>);
 
+// Счётчик id гранат: выдаёт только сервер, поэтому коллизий нет
+#[derive(Resource, Default)]
+pub struct NextGrenadeId(pub u64);
+
 #[derive(Resource)]
 pub struct RespawnDelay(pub f64); // секунды до респавна
 impl Default for RespawnDelay {
@@ -79,6 +101,12 @@ pub struct ConnectedClients(pub HashSet<u64>);
 #[derive(Resource, Default)]
 pub struct SpawnedClients(pub HashSet<u64>);
 
+#[derive(Resource, Default)]
+pub struct HandshakedClients(pub HashMap<u64, u32>); // client_id → согласованные capabilities
+
+#[derive(Resource, Default)]
+pub struct Nicknames(pub HashMap<u64, String>); // client_id → ник, заполняется на Hello
+
 #[derive(Resource, Default)]
 pub struct LastGrenadeThrows {
     pub map: HashMap<u64, f64>, // client_id → last throw time
@@ -87,9 +115,330 @@ pub struct LastGrenadeThrows {
 #[derive(Resource, Default)]
 pub struct GrenadeSyncTimer(pub Timer);
 
+// Патроны одного ствола
+#[derive(Clone)]
+pub struct WeaponState {
+    pub mag: u32,
+    pub reserve: u32,
+}
+
+// Оружие игрока: все стволы, активный и таймеры (авторитетно на сервере)
+#[derive(Clone)]
+pub struct Inventory {
+    pub active: WeaponKind,
+    pub weapons: HashMap<WeaponKind, WeaponState>,
+    pub last_shot: f64,
+    // когда пришёл бы следующий выстрел при ровном темпе (см. can_fire)
+    pub next_fire_at: f64,
+    pub spray: u32,                // номер выстрела в текущей очереди
+    pub reload_until: Option<f64>, // Some — идёт перезарядка активного
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        let weapons = WeaponKind::ALL
+            .iter()
+            .map(|&kind| {
+                let def = kind.def();
+                let state = WeaponState {
+                    mag: def.mag_size,
+                    reserve: def.reserve,
+                };
+                (kind, state)
+            })
+            .collect();
+        Self {
+            active: WeaponKind::default(),
+            weapons,
+            last_shot: f64::NEG_INFINITY,
+            next_fire_at: f64::NEG_INFINITY,
+            spray: 0,
+            reload_until: None,
+        }
+    }
+}
+
+impl Inventory {
+    pub fn active_def(&self) -> &'static WeaponDef {
+        self.active.def()
+    }
+
+    fn current(&self) -> &WeaponState {
+        &self.weapons[&self.active]
+    }
+
+    fn current_mut(&mut self) -> &mut WeaponState {
+        self.weapons.get_mut(&self.active).unwrap()
+    }
+
+    /// Можно ли выстрелить в момент `now` (патроны, перезарядка, темп).
+    /// Темп меряем по приходу на сервер, поэтому выстрел может прийти раньше ровного
+    /// расписания на FIRE_JITTER_SECS: опоздавший догоняется следующим, а чаще темпа
+    /// в среднем всё равно не выйдет
+    pub fn can_fire(&self, now: f64) -> bool {
+        self.reload_until.is_none()
+            && self.current().mag > 0
+            && now >= self.next_fire_at - FIRE_JITTER_SECS
+    }
+
+    pub fn fire(&mut self, now: f64) {
+        let def = self.active_def();
+        self.spray = next_spray(self.spray, now - self.last_shot, def);
+        self.next_fire_at = self.next_fire_at.max(now) + def.fire_interval;
+        self.current_mut().mag -= 1;
+        self.last_shot = now;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current().mag == 0
+    }
+
+    /// Начать перезарядку; false — если нечего/незачем перезаряжать
+    pub fn start_reload(&mut self, now: f64) -> bool {
+        let def = self.active_def();
+        let cur = self.current();
+        if self.reload_until.is_some() || cur.mag >= def.mag_size || cur.reserve == 0 {
+            return false;
+        }
+        self.reload_until = Some(now + def.reload_time);
+        true
+    }
+
+    pub fn finish_reload(&mut self) {
+        let mag_size = self.active_def().mag_size;
+        let cur = self.current_mut();
+        let take = (mag_size - cur.mag).min(cur.reserve);
+        cur.mag += take;
+        cur.reserve -= take;
+        self.reload_until = None;
+    }
+
+    /// Смена оружия прерывает перезарядку; false — если уже в руках
+    pub fn switch(&mut self, kind: WeaponKind) -> bool {
+        if self.active == kind {
+            return false;
+        }
+        // долг по темпу переносим, а интервал считаем уже по новому стволу
+        self.next_fire_at += kind.def().fire_interval - self.active_def().fire_interval;
+        self.active = kind;
+        self.reload_until = None;
+        true
+    }
+
+    pub fn ammo_msg(&self) -> S2C {
+        let cur = self.current();
+        S2C::Ammo {
+            weapon: self.active,
+            mag: cur.mag,
+            reserve: cur.reserve,
+            reloading: self.reload_until.is_some(),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct WeaponStates(pub HashMap<u64, Inventory>);
+
+// Карта, выбранная при запуске (--map)
+#[derive(Resource)]
+pub struct ActiveMap(pub GameMap);
+
+// Предмет на карте; None в respawn_at — лежит и его можно подобрать
+pub struct Pickup {
+    pub tile: Tile,
+    pub pos: Vec2,
+    pub respawn_at: Option<f64>,
+}
+
+// Аптечки и броня активной карты, в порядке GameMap::pickups — так их нумерует и клиент
+#[derive(Resource, Default)]
+pub struct Pickups(pub Vec<Pickup>);
+
+impl Pickups {
+    pub fn states_msg(&self) -> S2C {
+        S2C::PickupStates(self.0.iter().map(|p| p.respawn_at.is_none()).collect())
+    }
+}
+
+// Стены активной карты для общей симуляции движения (crate sim)
+#[derive(Resource, Default)]
+pub struct MapCollision(pub sim::CollisionWorld);
+
 // Ресурс карты, заполняется на клиенте при загрузке уровня (или из сервера).
 #[derive(Resource, Default)]
 pub struct SolidTiles(pub std::collections::HashSet<IVec2>);
 
 #[derive(Resource, Default, Clone)]
 pub struct SpawnPoints(pub Vec<Vec2>);
+
+// Зоны спавна команд ('T' / 'C' на карте)
+#[derive(Resource, Default, Clone)]
+pub struct TeamSpawns {
+    pub t: Vec<Vec2>,
+    pub ct: Vec<Vec2>,
+}
+
+#[derive(Resource, Default)]
+pub struct Teams(pub HashMap<u64, Team>);
+
+#[derive(Default, Clone)]
+pub struct PlayerScore {
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+}
+
+#[derive(Resource, Default)]
+pub struct Scores(pub HashMap<u64, PlayerScore>);
+
+// victim → (attacker → урон с последнего респавна); для ассистов
+#[derive(Resource, Default)]
+pub struct DamageLog(pub HashMap<u64, HashMap<u64, i32>>);
+
+#[derive(Resource, Default)]
+pub struct Latencies(pub HashMap<u64, f64>); // client_id → сглаженный RTT, secs
+
+#[derive(Resource, Default)]
+pub struct ScoreboardTimer(pub Timer); // как часто рассылать таблицу счёта
+
+// Состояние раунда; в дезматче всегда Warmup
+#[derive(Resource)]
+pub struct RoundState {
+    pub phase: RoundPhase,
+    pub phase_until: f64, // когда закончится текущая фаза (secs сервера)
+    pub round: u32,
+    pub score_t: u32,
+    pub score_ct: u32,
+}
+
+impl Default for RoundState {
+    fn default() -> Self {
+        Self {
+            phase: RoundPhase::Warmup,
+            phase_until: f64::INFINITY,
+            round: 0,
+            score_t: 0,
+            score_ct: 0,
+        }
+    }
+}
+
+impl RoundState {
+    pub fn is_frozen(&self) -> bool {
+        self.phase == RoundPhase::Freeze
+    }
+
+    // вне Warmup погибшие ждут следующего раунда
+    pub fn allows_respawn(&self) -> bool {
+        self.phase == RoundPhase::Warmup
+    }
+
+    pub fn info(&self, now: f64) -> RoundInfo {
+        RoundInfo {
+            phase: self.phase,
+            round: self.round,
+            time_left: (self.phase_until - now).max(0.0) as f32,
+            score_t: self.score_t,
+            score_ct: self.score_ct,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rifle() -> Inventory {
+        Inventory::default()
+    }
+
+    fn ammo(inv: &Inventory, kind: WeaponKind) -> (u32, u32) {
+        let st = &inv.weapons[&kind];
+        (st.mag, st.reserve)
+    }
+
+    #[test]
+    fn late_shot_is_caught_up_but_rate_is_kept() {
+        let mut inv = rifle();
+        let interval = inv.active_def().fire_interval;
+        inv.fire(0.0);
+        // первый выстрел задержала сеть, второй пришёл в том же кадре — оба честные
+        assert!(inv.can_fire(0.0));
+
+        // а засыпать сервер выстрелами каждые 10 мс: за секунду — не больше темпа и запаса
+        inv.weapons.get_mut(&WeaponKind::Rifle).unwrap().mag = 1000;
+        let mut fired = 0;
+        for i in 0..100 {
+            let now = i as f64 * 0.01;
+            if inv.can_fire(now) {
+                inv.fire(now);
+                fired += 1;
+            }
+        }
+        let limit = 1.0 / interval + FIRE_JITTER_SECS / interval;
+        assert!(fired as f64 <= limit + 1.0, "{fired} выстрелов за секунду");
+        assert!(fired as f64 >= 1.0 / interval);
+    }
+
+    #[test]
+    fn empty_magazine_reloads_from_reserve() {
+        let mut inv = rifle();
+        let def = inv.active_def();
+        let mut now = 0.0;
+        for _ in 0..def.mag_size {
+            assert!(inv.can_fire(now));
+            inv.fire(now);
+            now += def.fire_interval;
+        }
+        assert!(inv.is_empty());
+        assert!(!inv.can_fire(now + 10.0));
+        // пустой магазин — перезарядка стартует один раз, стрелять во время неё нельзя
+        assert!(inv.start_reload(now));
+        assert!(!inv.start_reload(now));
+        assert!(!inv.can_fire(now + 10.0));
+        inv.finish_reload();
+        assert_eq!(
+            ammo(&inv, WeaponKind::Rifle),
+            (def.mag_size, def.reserve - def.mag_size)
+        );
+        assert!(inv.can_fire(now + 10.0));
+    }
+
+    #[test]
+    fn reload_takes_only_what_is_left_in_reserve() {
+        // полный магазин перезаряжать незачем
+        assert!(!rifle().start_reload(0.0));
+
+        let mut inv = rifle();
+        let st = inv.weapons.get_mut(&WeaponKind::Rifle).unwrap();
+        (st.mag, st.reserve) = (25, 3);
+        assert!(inv.start_reload(0.0));
+        inv.finish_reload();
+        assert_eq!(ammo(&inv, WeaponKind::Rifle), (28, 0));
+        // запас кончился — перезаряжать нечем
+        assert!(!inv.start_reload(1.0));
+    }
+
+    #[test]
+    fn switch_cancels_reload_and_keeps_fire_rate() {
+        let mut inv = rifle();
+        inv.fire(0.0);
+        assert!(inv.start_reload(0.0));
+        assert!(!inv.switch(WeaponKind::Rifle), "уже в руках");
+        assert!(inv.switch(WeaponKind::Sniper));
+        assert_eq!(inv.reload_until, None);
+        let rifle_def = WeaponKind::Rifle.def();
+        assert_eq!(
+            ammo(&inv, WeaponKind::Rifle),
+            (rifle_def.mag_size - 1, rifle_def.reserve)
+        );
+
+        // туда-обратно темп снайперки не обойти
+        let interval = WeaponKind::Sniper.def().fire_interval;
+        inv.fire(10.0);
+        assert!(inv.switch(WeaponKind::Pistol));
+        assert!(inv.switch(WeaponKind::Sniper));
+        assert!(!inv.can_fire(10.0 + interval - FIRE_JITTER_SECS - 0.01));
+        assert!(inv.can_fire(10.0 + interval));
+    }
+}
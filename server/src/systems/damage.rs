@@ -1,33 +1,66 @@
 use bevy::prelude::*;
 use bevy_quinnet::server::QuinnetServer;
-use protocol::{constants::CH_S2C, messages::S2C};
+use protocol::{
+    constants::{ASSIST_MIN_DAMAGE, CH_S2C},
+    messages::S2C,
+};
 
 use crate::{
     events::DamageEvent,
-    resources::{PlayerStates, RespawnDelay, RespawnQueue, RespawnTask},
+    resources::{
+        DamageLog, PlayerStates, RespawnDelay, RespawnQueue, RespawnTask, RoundState, Scores,
+        SpawnPoints, SpawnedClients, TeamSpawns, Teams,
+    },
+    systems::spawn::spawn_point_for,
+    utils::{is_friendly_fire, split_damage},
 };
 
 pub fn apply_damage(
     mut ev_damage: EventReader<DamageEvent>,
     mut states: ResMut<PlayerStates>,
+    mut spawned: ResMut<SpawnedClients>,
     mut respawn_q: ResMut<RespawnQueue>,
     delay: Res<RespawnDelay>,
+    round: Res<RoundState>,
+    teams: Res<Teams>,
+    spawns: Res<SpawnPoints>,
+    team_spawns: Res<TeamSpawns>,
+    mut scores: ResMut<Scores>,
+    mut damage_log: ResMut<DamageLog>,
     time: Res<Time>,
     mut server: ResMut<QuinnetServer>,
 ) {
     let now = time.elapsed_secs_f64();
     for ev in ev_damage.read() {
         // println!("[DEBUG] damage event player:{:?} {:?}", ev.target, ev.amount);
+        // огня по своим нет — значит, нет и командных фрагов
+        if is_friendly_fire(&teams.0, ev.source, ev.target) {
+            continue;
+        }
         if let Some(st) = states.0.get_mut(&ev.target) {
-            st.hp -= ev.amount;
+            // часть урона берёт на себя броня
+            let (hp_loss, armor_loss) = split_damage(ev.amount, st.armor);
+            // запоминаем фактически снятое HP — для ассистов
+            if let Some(src) = ev.source.filter(|&src| src != ev.target) {
+                let dealt = hp_loss.min(st.hp.max(0));
+                let per_attacker = damage_log.0.entry(ev.target).or_default();
+                *per_attacker.entry(src).or_default() += dealt;
+            }
+            st.hp -= hp_loss;
+            st.armor -= armor_loss;
 
             // todo not work info! here
             println!(
-                "🩸 Player {} took {} dmg (hp={})",
-                ev.target, ev.amount, st.hp
+                "🩸 Player {} took {} dmg (hp={}, armor={})",
+                ev.target, ev.amount, st.hp, st.armor
             );
 
             let endpoint = server.endpoint_mut();
+            if armor_loss > 0 {
+                endpoint
+                    .send_message_on(ev.target, CH_S2C, S2C::Armor(st.armor))
+                    .ok();
+            }
 
             // send damage event
             endpoint
@@ -36,7 +69,7 @@ pub fn apply_damage(
                     S2C::PlayerDamaged {
                         id: ev.target,
                         new_hp: st.hp,
-                        damage: ev.amount,
+                        damage: hp_loss,
                     },
                 )
                 .ok();
@@ -49,19 +82,28 @@ pub fn apply_damage(
                         S2C::PlayerDied {
                             victim: ev.target,
                             killer: ev.source,
+                            cause: ev.cause,
                         },
                     )
                     .unwrap();
                 info!("💀 [Server] Player {} died", ev.target);
+                credit_kill(&mut scores, &mut damage_log, ev.target, ev.source);
 
                 // 2) удаляем состояние и планируем респавн
                 states.0.remove(&ev.target);
+                // до респавна игрок не заспавнен — стрелять не может
+                spawned.0.remove(&ev.target);
 
                 // ОЧИЩАЕМ предыдущие задачи для этого игрока
                 respawn_q.0.retain(|task| task.pid != ev.target);
 
+                // в режиме раундов погибший ждёт следующего раунда
+                if !round.allows_respawn() {
+                    continue;
+                }
+
                 // ставим задачу на время now + delay
-                let spawn_pos = pick_spawn_point(ev.target);
+                let spawn_pos = spawn_point_for(&spawns, &team_spawns, &teams, ev.target);
                 respawn_q.0.push(RespawnTask {
                     pid: ev.target,
                     due: now + delay.0,
@@ -78,16 +120,77 @@ pub fn apply_damage(
     }
 }
 
-// todo загружать логику спавнов отдельно
-/// Простая функция, возвращающая точку спавна по ID.
-/// Замените логику на свою: рандом, круг, свободные точки и т.д.
-fn pick_spawn_point(pid: u64) -> Vec2 {
-    const POINTS: [Vec2; 4] = [
-        Vec2::new(-300.0, -200.0),
-        Vec2::new(300.0, -200.0),
-        Vec2::new(-300.0, 200.0),
-        Vec2::new(300.0, 200.0),
-    ];
-    let idx = (pid as usize) % POINTS.len();
-    POINTS[idx]
+/// Смерть, фраг убийце (самоубийство не в счёт) и ассисты остальным, кто хорошо попал
+fn credit_kill(scores: &mut Scores, log: &mut DamageLog, victim: u64, killer: Option<u64>) {
+    scores.0.entry(victim).or_default().deaths += 1;
+    let killer = killer.filter(|&k| k != victim);
+    if let Some(k) = killer {
+        scores.0.entry(k).or_default().kills += 1;
+    }
+
+    let Some(attackers) = log.0.remove(&victim) else {
+        return;
+    };
+    for (attacker, dealt) in attackers {
+        if Some(attacker) != killer && dealt >= ASSIST_MIN_DAMAGE {
+            scores.0.entry(attacker).or_default().assists += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const KILLER: u64 = 1;
+    const VICTIM: u64 = 2;
+    const HELPER: u64 = 3;
+    const GRAZER: u64 = 4;
+
+    fn score(scores: &Scores, id: u64) -> (u32, u32, u32) {
+        scores
+            .0
+            .get(&id)
+            .map_or((0, 0, 0), |s| (s.kills, s.deaths, s.assists))
+    }
+
+    #[test]
+    fn kill_goes_to_killer_and_assist_to_helper() {
+        let mut scores = Scores::default();
+        let mut log = DamageLog::default();
+        log.0.insert(
+            VICTIM,
+            HashMap::from([
+                (KILLER, 60),
+                (HELPER, ASSIST_MIN_DAMAGE),
+                (GRAZER, ASSIST_MIN_DAMAGE - 1),
+            ]),
+        );
+        log.0.insert(KILLER, HashMap::from([(VICTIM, 30)]));
+
+        credit_kill(&mut scores, &mut log, VICTIM, Some(KILLER));
+
+        assert_eq!(score(&scores, KILLER), (1, 0, 0));
+        assert_eq!(score(&scores, VICTIM), (0, 1, 0));
+        assert_eq!(score(&scores, HELPER), (0, 0, 1));
+        assert_eq!(score(&scores, GRAZER), (0, 0, 0));
+        // урон по погибшему забыт, по остальным — нет
+        assert!(!log.0.contains_key(&VICTIM));
+        assert!(log.0.contains_key(&KILLER));
+    }
+
+    #[test]
+    fn suicide_is_only_a_death() {
+        let mut scores = Scores::default();
+        let mut log = DamageLog::default();
+        log.0.insert(VICTIM, HashMap::from([(HELPER, 50)]));
+
+        credit_kill(&mut scores, &mut log, VICTIM, Some(VICTIM));
+
+        assert_eq!(score(&scores, VICTIM), (0, 1, 0));
+        // кто ранил перед самоподрывом — получает ассист
+        assert_eq!(score(&scores, HELPER), (0, 0, 1));
+        assert!(log.0.is_empty());
+    }
 }
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use protocol::map::Tile;
+use sim::CollisionWorld;
+use std::collections::HashSet;
+
+use crate::{
+    resources::{ActiveMap, MapCollision, Pickup, Pickups, SolidTiles, SpawnPoints, TeamSpawns},
+    systems::wall::Wall,
+};
+
+/// Построение уровня по карте: спавнит стены, возвращает SolidTiles, SpawnPoints и командные спавны
+pub fn create_map_level(
+    commands: &mut Commands,
+    map: &ActiveMap,
+) -> (SolidTiles, Vec<Vec2>, TeamSpawns) {
+    let mut solid: HashSet<IVec2> = HashSet::new();
+    let mut spawns: Vec<Vec2> = Vec::new();
+    let mut team_spawns = TeamSpawns::default();
+
+    for (x, y, tile) in map.0.tiles() {
+        let world_xy = map.0.tile_center(x, y);
+
+        match tile {
+            Tile::Wall => {
+                solid.insert(IVec2::new(x as i32, y as i32));
+            }
+            Tile::Spawn => {
+                spawns.push(world_xy);
+            }
+            // командные точки годятся и для дезматча
+            Tile::SpawnT => {
+                spawns.push(world_xy);
+                team_spawns.t.push(world_xy);
+            }
+            Tile::SpawnCt => {
+                spawns.push(world_xy);
+                team_spawns.ct.push(world_xy);
+            }
+            // предметы лежат на проходимой клетке; список собирает setup_map_level
+            Tile::Empty | Tile::HealthPack | Tile::ArmorVest => {}
+        }
+    }
+
+    // стены рисуем слитыми прямоугольниками: спрайт на блок, а не на клетку
+    for r in map.0.wall_rects() {
+        commands.spawn((
+            Sprite {
+                color: Color::srgba(0.25, 0.28, 0.34, 1.0),
+                custom_size: Some(r.size()),
+                ..default()
+            },
+            Transform::from_translation(map.0.rect_center(&r).extend(0.0)),
+            GlobalTransform::default(),
+            Wall, // твой маркер стены
+        ));
+    }
+
+    (SolidTiles(solid), spawns, team_spawns)
+}
+
+/// Системный сетап: один раз строим уровень и кладём ресурсы
+pub fn setup_map_level(mut commands: Commands, map: Res<ActiveMap>) {
+    let (solid, spawns, team_spawns) = create_map_level(&mut commands, &map);
+    info!(
+        "🗺 Карта «{}» {}x{}: спавнов {}",
+        map.0.name,
+        map.0.width,
+        map.0.height,
+        spawns.len()
+    );
+
+    // сетка стен для всех коллизий и лучей сервера
+    let collision = CollisionWorld::from_tiles(
+        -map.0.half_size(),
+        map.0.width,
+        map.0.height,
+        solid.0.iter().copied(),
+    );
+    commands.insert_resource(MapCollision(collision));
+    let pickups = map.0.pickups().into_iter().map(|(tile, pos)| Pickup {
+        tile,
+        pos,
+        respawn_at: None,
+    });
+    commands.insert_resource(Pickups(pickups.collect()));
+    commands.insert_resource(solid);
+    commands.insert_resource(SpawnPoints(spawns));
+    commands.insert_resource(team_spawns);
+}
@@ -8,4 +8,8 @@ pub mod connection;
 pub mod spawn;
 pub mod respawn_timers;
 pub mod wall;
-pub mod level_fixed;
\ No newline at end of file
+pub mod level_map;
+pub mod weapons;
+pub mod rounds;
+pub mod scoreboard;
+pub mod pickups;
@@ -0,0 +1,71 @@
+use crate::resources::{
+    HandshakedClients, MapCollision, Pickups, PlayerStates, SpawnedClients, Teams,
+};
+use crate::utils::{apply_pickup, is_visible_to};
+use bevy::prelude::*;
+use bevy_quinnet::server::QuinnetServer;
+use protocol::constants::{CH_S2C, PICKUP_RADIUS, PICKUP_RESPAWN_SECS};
+use protocol::map::Tile;
+use protocol::messages::S2C;
+
+/// Возвращаем подобранные предметы по таймеру и отдаём лежащие живым игрокам рядом;
+/// при любом изменении рассылаем всем, что где лежит
+pub fn update_pickups(
+    mut pickups: ResMut<Pickups>,
+    mut states: ResMut<PlayerStates>,
+    spawned: Res<SpawnedClients>,
+    handshaked: Res<HandshakedClients>,
+    teams: Res<Teams>,
+    collision: Res<MapCollision>,
+    mut server: ResMut<QuinnetServer>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs_f64();
+    let endpoint = server.endpoint_mut();
+    let mut changed = false;
+
+    for pickup in pickups.0.iter_mut() {
+        if pickup.respawn_at.is_some_and(|at| now >= at) {
+            pickup.respawn_at = None;
+            changed = true;
+        }
+        if pickup.respawn_at.is_some() {
+            continue;
+        }
+        // с полным HP аптечку не подбирают — достанется следующему
+        let taker = states.0.iter_mut().find_map(|(&id, st)| {
+            let near = st.hp > 0 && st.pos.distance(pickup.pos) <= PICKUP_RADIUS;
+            (near && spawned.0.contains(&id) && apply_pickup(pickup.tile, st))
+                .then_some((id, st.pos, st.hp, st.armor))
+        });
+        let Some((id, pos, hp, armor)) = taker else {
+            continue;
+        };
+        pickup.respawn_at = Some(now + PICKUP_RESPAWN_SECS);
+        changed = true;
+        match pickup.tile {
+            Tile::ArmorVest => {
+                endpoint.send_message_on(id, CH_S2C, S2C::Armor(armor)).ok();
+            }
+            // лечение выдаёт позицию — тот же фильтр, что и у снапшотов
+            _ => {
+                let msg = S2C::PlayerHealed { id, new_hp: hp };
+                for &viewer in handshaked.0.keys() {
+                    if is_visible_to(viewer, id, pos, &states.0, &teams.0, &collision.0) {
+                        endpoint.send_message_on(viewer, CH_S2C, msg.clone()).ok();
+                    }
+                }
+            }
+        }
+        info!(
+            "🎁 Игрок {id} подобрал {:?} (hp={hp}, armor={armor})",
+            pickup.tile
+        );
+    }
+
+    if changed {
+        endpoint
+            .broadcast_message_on(CH_S2C, pickups.states_msg())
+            .ok();
+    }
+}
@@ -1,29 +1,83 @@
-use crate::events::DamageEvent;
+use crate::config::ServerConfig;
+use crate::events::{ClientDisconnected, DamageEvent, PlayerJoined};
 use crate::resources::{
-    AppliedSeqs, GrenadeState, Grenades, LastGrenadeThrows, LastHeard, PendingInputs, PlayerStates,
-    SnapshotHistory,
+    ActiveMap, AppliedSeqs, GrenadeState, Grenades, HandshakedClients, LastGrenadeThrows,
+    LastHeard, Latencies, MapCollision, NextGrenadeId, Nicknames, PendingInputs, PlayerStates,
+    RoundState, ServerTick, ShotSeqs, SnapshotAcks, SnapshotHistory, SpawnedClients, Teams,
+    WeaponStates,
 };
-use crate::systems::wall::Wall;
-use crate::utils::{check_hit_lag_comp, push_history};
+use crate::utils::{
+    check_hit_lag_comp, clamp_client_value, clamp_rewind, is_player_alive, is_visible_to,
+    los_blocked_by_walls, moved_last_tick, next_shot_seq, queue_input, validate_nickname,
+};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_quinnet::server::QuinnetServer;
 use protocol::constants::{
-    CH_C2S, CH_S2C, GRENADE_RADIUS, GRENADE_SPEED, GRENADE_TIMER, GRENADE_USAGE_COOLDOWN,
-    SHOOT_RIFLE_DAMAGE,
+    CH_C2S, CH_S2C, CH_S2C_UNRELIABLE, GRENADE_RADIUS, GRENADE_SPEED, GRENADE_TIMER,
+    GRENADE_USAGE_COOLDOWN, PROTOCOL_VERSION, SUPPORTED_CAPABILITIES,
 };
-use protocol::messages::{C2S, GrenadeEvent, S2C, ShootFx};
+use protocol::messages::{C2S, DeathCause, GrenadeEvent, S2C, ShootFx};
+use protocol::weapons::ShotSpread;
+use sim::GrenadeBody;
+use std::collections::HashMap;
+
+// Всё про гранаты одним параметром (у системы лимит в 16 параметров)
+#[derive(SystemParam)]
+pub struct GrenadeCtx<'w> {
+    pub grenades: ResMut<'w, Grenades>,
+    pub last_throw: ResMut<'w, LastGrenadeThrows>,
+    pub next_id: ResMut<'w, NextGrenadeId>,
+}
+
+// Очереди инпутов и последний применённый номер
+#[derive(SystemParam)]
+pub struct Inputs<'w> {
+    pub pending: ResMut<'w, PendingInputs>,
+    pub applied: Res<'w, AppliedSeqs>,
+}
+
+// Когда клиента слышали последний раз, его пинг и подтверждённый снапшот
+#[derive(SystemParam)]
+pub struct LinkStats<'w> {
+    pub last_heard: ResMut<'w, LastHeard>,
+    pub latencies: ResMut<'w, Latencies>,
+    pub snapshot_acks: ResMut<'w, SnapshotAcks>,
+}
+
+// Лаг-компенсация: история, текущий тик, предел отката, стены для лучей и номера выстрелов
+#[derive(SystemParam)]
+pub struct Hitscan<'w> {
+    pub shot_seqs: ResMut<'w, ShotSeqs>,
+    pub history: Res<'w, SnapshotHistory>,
+    pub tick: Res<'w, ServerTick>,
+    pub config: Res<'w, ServerConfig>,
+    pub collision: Res<'w, MapCollision>,
+}
+
+// Рукопожатие: кто его прошёл, под каким ником и какую карту отдаём
+#[derive(SystemParam)]
+pub struct Session<'w> {
+    pub handshaked: ResMut<'w, HandshakedClients>,
+    pub nicknames: ResMut<'w, Nicknames>,
+    pub map: Res<'w, ActiveMap>,
+}
 
 pub fn process_c2s_messages(
     mut server: ResMut<QuinnetServer>,
-    mut pending: ResMut<PendingInputs>,
-    mut states: ResMut<PlayerStates>,
-    mut last_heard: ResMut<LastHeard>,
-    mut applied: ResMut<AppliedSeqs>,
-    mut history: ResMut<SnapshotHistory>,
-    mut grenades: ResMut<Grenades>,
-    mut last_grenade: ResMut<LastGrenadeThrows>,
+    mut inputs: Inputs,
+    states: Res<PlayerStates>,
+    mut link: LinkStats,
+    mut hitscan: Hitscan,
+    mut gren: GrenadeCtx,
+    mut session: Session,
+    spawned: Res<SpawnedClients>,
+    mut weapons: ResMut<WeaponStates>,
+    round: Res<RoundState>,
+    teams: Res<Teams>,
     mut damage_events: EventWriter<DamageEvent>,
-    wall_q: Query<(&Transform, &Sprite), With<Wall>>,
+    mut joined_events: EventWriter<PlayerJoined>,
+    mut left_events: EventWriter<ClientDisconnected>,
     time: Res<Time>,
 ) {
     let now = time.elapsed_secs_f64();
@@ -34,35 +88,232 @@ pub fn process_c2s_messages(
             debug_assert_eq!(chan, CH_C2S);
 
             // помечаем время последнего сообщения
-            last_heard.0.insert(client_id, now);
+            link.last_heard.0.insert(client_id, now);
 
             match msg {
-                C2S::Input(input) => {
-                    pending.0.entry(client_id).or_default().push_back(input);
+                C2S::Hello {
+                    protocol_version,
+                    client_name,
+                    capabilities,
+                    nickname,
+                } => {
+                    if session.handshaked.0.contains_key(&client_id) {
+                        continue; // повторный Hello игнорим
+                    }
+                    if protocol_version != PROTOCOL_VERSION {
+                        let reason = format!(
+                            "версия протокола клиента {} не совпадает с серверной {}",
+                            protocol_version, PROTOCOL_VERSION
+                        );
+                        warn!("⛔ Клиент {client_id} ({client_name}) отклонён: {reason}");
+                        endpoint
+                            .send_message_on(client_id, CH_S2C, S2C::Rejected { reason })
+                            .ok();
+                        continue;
+                    }
+
+                    let taken = session.nicknames.0.values();
+                    let nickname = match validate_nickname(&nickname, taken) {
+                        Ok(nick) => nick,
+                        Err(reason) => {
+                            warn!("⛔ Клиент {client_id} ({client_name}) отклонён: {reason}");
+                            endpoint
+                                .send_message_on(client_id, CH_S2C, S2C::Rejected { reason })
+                                .ok();
+                            continue;
+                        }
+                    };
+
+                    let caps = capabilities & SUPPORTED_CAPABILITIES;
+                    session.handshaked.0.insert(client_id, caps);
+                    endpoint
+                        .send_message_on(
+                            client_id,
+                            CH_S2C,
+                            S2C::Welcome {
+                                protocol_version: PROTOCOL_VERSION,
+                                client_id,
+                                capabilities: caps,
+                            },
+                        )
+                        .ok();
+                    let map = &session.map.0;
+                    endpoint
+                        .send_message_on(
+                            client_id,
+                            CH_S2C,
+                            S2C::MapInfo {
+                                name: map.name.clone(),
+                                hash: map.hash(),
+                            },
+                        )
+                        .ok();
+                    joined_events.write(PlayerJoined(client_id));
+
+                    info!(
+                        "🤝 Клиент {client_id} «{nickname}» ({client_name}) прошёл рукопожатие, caps={caps:#b}"
+                    );
+                    session.nicknames.0.insert(client_id, nickname);
+                }
+                // до рукопожатия принимаем только служебные сообщения
+                C2S::Input(_)
+                | C2S::Shoot(_)
+                | C2S::ThrowGrenade(_)
+                | C2S::Reload
+                | C2S::SwitchWeapon(_)
+                | C2S::PingEcho { .. }
+                | C2S::RequestMap
+                | C2S::SnapshotAck { .. }
+                    if !session.handshaked.0.contains_key(&client_id) =>
+                {
+                    debug!("Клиент {client_id} шлёт игровые сообщения до Hello — игнор");
+                }
+                C2S::Input(packed) => {
+                    let Some(input) = packed.unpack() else {
+                        debug!("Клиент {client_id} прислал битый инпут — игнор");
+                        continue;
+                    };
+                    let applied = inputs.applied.0.get(&client_id).copied();
+                    let queue = inputs.pending.0.entry(client_id).or_default();
+                    queue_input(queue, input, applied);
                 }
                 C2S::Shoot(shoot) => {
-                    // println!("🔫 [Server] ShootEvent from {}: {:?}", client_id, shoot);
-                    if let Some(hit) = check_hit_lag_comp(&history.buf, &states.0, &shoot, &wall_q)
+                    // номер считаем у каждого дошедшего по порядку выстрела, даже отклонённого
+                    // ниже: иначе клиент и сервер разойдутся в сидах разброса
+                    if let Err(expected) =
+                        next_shot_seq(&mut hitscan.shot_seqs.0, client_id, shoot.seq)
                     {
-                        println!("💥 [Server] hit target {}", hit);
+                        warn!(
+                            "🚫 Клиент {client_id}: выстрел №{} вместо №{expected} — отклонён",
+                            shoot.seq
+                        );
+                        continue;
+                    }
+                    // стрелок — всегда владелец соединения; мёртвые и незаспавненные не стреляют
+                    if !is_player_alive(client_id, &spawned.0, &states.0) {
+                        debug!("Клиент {client_id} стреляет, будучи мёртвым — игнор");
+                        continue;
+                    }
+                    if round.is_frozen() {
+                        continue; // заморозка: стрелять нельзя
+                    }
+                    let max_rewind = hitscan.config.max_rewind_ticks();
+                    let shoot = match clamp_rewind(&shoot, hitscan.tick.0, max_rewind) {
+                        Ok(clamped) => {
+                            if clamped.tick != shoot.tick {
+                                debug!(
+                                    "Клиент {client_id}: откат с тика {} прижат к {} (предел {} мс)",
+                                    shoot.tick, clamped.tick, hitscan.config.max_rewind_ms
+                                );
+                            }
+                            clamped
+                        }
+                        Err(reason) => {
+                            warn!("🚫 Клиент {client_id}: выстрел отклонён — {reason}");
+                            continue;
+                        }
+                    };
+
+                    // темп стрельбы и патроны активного оружия
+                    let Some(inventory) = weapons.0.get_mut(&client_id) else {
+                        continue;
+                    };
+                    if !inventory.can_fire(now) {
+                        // пустой магазин — сразу уходим в перезарядку
+                        if inventory.is_empty() && inventory.start_reload(now) {
+                            endpoint
+                                .send_message_on(client_id, CH_S2C, inventory.ammo_msg())
+                                .ok();
+                        }
+                        debug!("Клиент {client_id}: выстрел отклонён (темп/патроны)");
+                        continue;
+                    }
+                    inventory.fire(now);
+                    endpoint
+                        .send_message_on(client_id, CH_S2C, inventory.ammo_msg())
+                        .ok();
+                    let weapon = inventory.active;
+                    let def = weapon.def();
+                    // очередь и движение берём худшие из клиентских и серверных: не сжульничать,
+                    // а при совпадении трассер клиента ляжет ровно по лучу сервера
+                    let shot = ShotSpread {
+                        seq: shoot.seq,
+                        spray: shoot.spray.max(inventory.spray),
+                        moving: shoot.moving || moved_last_tick(&hitscan.history.buf, client_id),
+                        stance: states
+                            .0
+                            .get(&client_id)
+                            .map(|st| st.stance.clone())
+                            .unwrap_or_default(),
+                    };
+
+                    // println!("🔫 [Server] ShootEvent from {}: {:?}", client_id, shoot);
+                    // по лучу на дробину; урон по одной цели суммируем в одно событие
+                    let hits = check_hit_lag_comp(
+                        &hitscan.history.buf,
+                        client_id,
+                        &shoot,
+                        def,
+                        &shot,
+                        |a, b| los_blocked_by_walls(a, b, &hitscan.collision.0),
+                    );
+                    let mut damage_by_target: HashMap<u64, f32> = HashMap::new();
+                    for hit in hits {
+                        *damage_by_target.entry(hit).or_default() += def.damage;
+                    }
+                    for (hit, amount) in damage_by_target {
+                        debug!("💥 Клиент {client_id} попал в {hit} ({})", def.name);
 
                         damage_events.write(DamageEvent {
                             target: hit,
-                            amount: SHOOT_RIFLE_DAMAGE as i32,
-                            source: Some(shoot.shooter_id),
+                            amount: amount as i32,
+                            source: Some(client_id),
+                            cause: DeathCause::Weapon(weapon),
                         });
                     }
 
-                    if let Some(st) = states.0.get(&shoot.shooter_id) {
-                        let fx = ShootFx {
-                            shooter_id: shoot.shooter_id,
+                    if let Some(st) = states.0.get(&client_id) {
+                        let fx = S2C::ShootFx(ShootFx {
+                            shooter_id: client_id,
+                            weapon,
+                            shot,
                             from: st.pos, // используем позицию игрока из состояния
                             dir: shoot.dir,
-                            timestamp: shoot.timestamp,
-                        };
+                            timestamp: now,
+                        });
+                        // трассер выдаёт позицию стрелка — только тем, кому он и так виден
+                        let walls = &hitscan.collision.0;
+                        for &viewer in session.handshaked.0.keys() {
+                            if is_visible_to(viewer, client_id, st.pos, &states.0, &teams.0, walls)
+                            {
+                                endpoint
+                                    .send_message_on(viewer, CH_S2C_UNRELIABLE, fx.clone())
+                                    .ok();
+                            }
+                        }
+                    }
+                }
+                C2S::Reload => {
+                    if !is_player_alive(client_id, &spawned.0, &states.0) {
+                        continue;
+                    }
+                    if let Some(inventory) = weapons.0.get_mut(&client_id)
+                        && inventory.start_reload(now)
+                    {
+                        endpoint
+                            .send_message_on(client_id, CH_S2C, inventory.ammo_msg())
+                            .ok();
+                        debug!("🔄 Клиент {client_id} начал перезарядку");
+                    }
+                }
+                C2S::SwitchWeapon(kind) => {
+                    if let Some(inventory) = weapons.0.get_mut(&client_id)
+                        && inventory.switch(kind)
+                    {
                         endpoint
-                            .broadcast_message_on(CH_S2C, S2C::ShootFx(fx))
-                            .unwrap();
+                            .send_message_on(client_id, CH_S2C, inventory.ammo_msg())
+                            .ok();
+                        debug!("🔫 Клиент {client_id} взял {}", kind.def().name);
                     }
                 }
                 C2S::Heartbeat => {
@@ -70,15 +321,9 @@ pub fn process_c2s_messages(
                 }
                 // Клиент корректно сообщил, что уходит
                 C2S::Goodbye => {
-                    states.0.remove(&client_id);
-                    pending.0.remove(&client_id);
-                    applied.0.remove(&client_id);
-
-                    endpoint
-                        .broadcast_message_on(CH_S2C, S2C::PlayerLeft(client_id))
-                        .ok();
-
-                    info!("👋 Клиент {client_id} ушёл - broadcast PlayerLeft");
+                    // уборка (в т.ч. команды и счёт) — там же, где и при обрыве соединения
+                    left_events.write(ClientDisconnected(client_id));
+                    info!("👋 Клиент {client_id} попрощался");
                 }
                 C2S::Ping(client_ts) => {
                     let server_ts = time.elapsed_secs_f64();
@@ -95,10 +340,53 @@ pub fn process_c2s_messages(
                         )
                         .ok();
                 }
+                C2S::PingEcho { server_time } => {
+                    // RTT по серверным часам, сглаживаем скачки
+                    let rtt = (now - server_time).max(0.0);
+                    link.latencies
+                        .0
+                        .entry(client_id)
+                        .and_modify(|avg| *avg = *avg * 0.8 + rtt * 0.2)
+                        .or_insert(rtt);
+                }
+                C2S::RequestMap => {
+                    let map = &session.map.0;
+                    info!("🗺 Клиент {client_id} скачивает карту «{}»", map.name);
+                    endpoint
+                        .send_message_on(
+                            client_id,
+                            CH_S2C,
+                            S2C::MapData {
+                                name: map.name.clone(),
+                                data: map.to_text(),
+                            },
+                        )
+                        .ok();
+                }
+                C2S::SnapshotAck { tick } => {
+                    // подтверждения могут прийти не по порядку — база только растёт
+                    link.snapshot_acks
+                        .0
+                        .entry(client_id)
+                        .and_modify(|acked| *acked = (*acked).max(tick))
+                        .or_insert(tick);
+                }
                 C2S::ThrowGrenade(ev) => {
+                    // бросать может только живой игрок; точку вылета берём из его состояния
+                    if !is_player_alive(client_id, &spawned.0, &states.0) {
+                        debug!("Клиент {client_id} бросает гранату, будучи мёртвым — игнор");
+                        continue;
+                    }
+                    if round.is_frozen() {
+                        continue;
+                    }
+                    let Some(st) = states.0.get(&client_id) else {
+                        continue;
+                    };
+
                     let cooldown = GRENADE_USAGE_COOLDOWN;
 
-                    let can_throw = match last_grenade.map.get(&client_id) {
+                    let can_throw = match gren.last_throw.map.get(&client_id) {
                         Some(&last_time) => now - last_time >= cooldown,
                         None => true,
                     };
@@ -111,58 +399,58 @@ pub fn process_c2s_messages(
                         continue; // Пропускаем бросок
                     }
 
-                    // Обновляем время последнего броска
-                    last_grenade.map.insert(client_id, now);
-
                     // Нормализуем присланный вектор (на всякий случай)
                     let mut dir = ev.dir;
-                    if dir.length_squared() <= f32::EPSILON {
+                    if !dir.is_finite() || dir.length_squared() <= f32::EPSILON {
                         // мусорный ввод — игнорим
                         continue;
                     }
                     dir = dir.normalize();
 
+                    // Обновляем время последнего броска
+                    gren.last_throw.map.insert(client_id, now);
+
+                    // скорость и таймер — не больше стандартных
+                    let speed = clamp_client_value(ev.speed, GRENADE_SPEED);
+                    let timer = clamp_client_value(ev.timer, GRENADE_TIMER);
+
                     // Смещаем точку спавна вперёд по направлению (радиус + небольшой запас),
                     // чтобы не родиться впритык к стене/игроку
-                    let spawn_from = ev.from + dir * (GRENADE_RADIUS + 1.0);
+                    let spawn_from = st.pos + dir * (GRENADE_RADIUS + 1.0);
+
+                    gren.next_id.0 += 1;
+                    let grenade = GrenadeEvent {
+                        id: gren.next_id.0,
+                        from: spawn_from,
+                        dir,
+                        speed,
+                        timer,
+                        // время броска по часам сервера — от него клиент считает тики полёта
+                        timestamp: now,
+                    };
 
                     // Заводим серверное состояние
-                    grenades.0.insert(
-                        ev.id,
+                    gren.grenades.0.insert(
+                        grenade.id,
                         GrenadeState {
-                            ev: GrenadeEvent {
-                                id: ev.id,
-                                from: spawn_from,
-                                dir,             // нормализованный
-                                speed: ev.speed, // фактический из клиента (или оставь константу, если у тебя фикс)
-                                timer: ev.timer, // фактический из клиента
-                                timestamp: ev.timestamp,
-                            },
+                            ev: grenade.clone(),
+                            owner: client_id,
                             created: now,
-                            pos: spawn_from,
-                            vel: dir * ev.speed,
+                            body: GrenadeBody {
+                                pos: spawn_from,
+                                vel: dir * speed,
+                            },
+                            ticks: 0,
                         },
                     );
 
-                    let grenade_id = ev.id;
                     // и рассылаем всем клиентам, чтобы они визуализировали гранату
-                    let _ = endpoint.broadcast_message_on(
-                        CH_S2C,
-                        S2C::GrenadeSpawn(GrenadeEvent {
-                            id: ev.id,
-                            from: spawn_from,
-                            dir,
-                            speed: ev.speed, // не подменяем на константу
-                            timer: ev.timer,
-                            timestamp: ev.timestamp,
-                        }),
-                    );
+                    let _ =
+                        endpoint.broadcast_message_on(CH_S2C, S2C::GrenadeSpawn(grenade.clone()));
 
-                    info!("💣 Клиент {} бросил гранату {}", client_id, grenade_id);
+                    info!("💣 Клиент {} бросил гранату {}", client_id, grenade.id);
                 }
             }
         }
     }
-
-    push_history(&mut history, now, &states.0);
 }
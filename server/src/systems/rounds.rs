@@ -0,0 +1,279 @@
+use crate::{
+    resources::{
+        DamageLog, Inventory, PlayerState, PlayerStates, RespawnQueue, RoundState, SpawnPoints,
+        SpawnedClients, TeamSpawns, Teams, WeaponStates,
+    },
+    utils::is_player_alive,
+};
+use bevy::prelude::*;
+use bevy_quinnet::server::QuinnetServer;
+use protocol::{
+    constants::{CH_S2C, MAX_HP, ROUND_END_TIME, ROUND_FREEZE_TIME, ROUND_TIME},
+    messages::{RoundPhase, S2C, Team},
+};
+
+/// Новичка — в команду, где меньше игроков (при равенстве — T)
+pub fn pick_team(teams: &Teams) -> Team {
+    let t = teams
+        .0
+        .values()
+        .filter(|&&team| team == Team::Terrorists)
+        .count();
+    let ct = teams.0.len() - t;
+    if ct < t {
+        Team::CounterTerrorists
+    } else {
+        Team::Terrorists
+    }
+}
+
+/// Точка спавна команды; если на карте нет зон 'T'/'C' — общие точки
+pub fn team_spawn_point(
+    team_spawns: &TeamSpawns,
+    fallback: &SpawnPoints,
+    team: Team,
+    index: usize,
+) -> Vec2 {
+    let points = match team {
+        Team::Terrorists => &team_spawns.t,
+        Team::CounterTerrorists => &team_spawns.ct,
+    };
+    let points = if points.is_empty() {
+        &fallback.0
+    } else {
+        points
+    };
+    if points.is_empty() {
+        return Vec2::ZERO;
+    }
+    points[index % points.len()]
+}
+
+/// Машина состояний раунда: Warmup → Freeze → Live → Ended → Freeze …
+/// `alive` — живые (T, CT). Возвращает false, если фаза не сменилась
+pub fn advance_round(
+    round: &mut RoundState,
+    now: f64,
+    both_teams: bool,
+    alive: (usize, usize),
+) -> bool {
+    let mut start_round = false;
+    match round.phase {
+        RoundPhase::Warmup => {
+            if !both_teams {
+                return false;
+            }
+            start_round = true;
+        }
+        RoundPhase::Freeze => {
+            if now < round.phase_until {
+                return false;
+            }
+            round.phase = RoundPhase::Live;
+            round.phase_until = now + ROUND_TIME;
+            info!("🟢 Раунд {} начался", round.round);
+        }
+        RoundPhase::Live => {
+            let winner = match alive {
+                (0, 0) => None,
+                (0, _) => Some(Team::CounterTerrorists),
+                (_, 0) => Some(Team::Terrorists),
+                // время вышло — раунд за CT
+                _ if now >= round.phase_until => Some(Team::CounterTerrorists),
+                _ => return false,
+            };
+            match winner {
+                Some(Team::Terrorists) => round.score_t += 1,
+                Some(Team::CounterTerrorists) => round.score_ct += 1,
+                None => {}
+            }
+            round.phase = RoundPhase::Ended { winner };
+            round.phase_until = now + ROUND_END_TIME;
+            info!(
+                "🏁 Раунд {} окончен, победитель: {:?} (T {} : {} CT)",
+                round.round, winner, round.score_t, round.score_ct
+            );
+        }
+        RoundPhase::Ended { .. } => {
+            if now < round.phase_until {
+                return false;
+            }
+            if both_teams {
+                start_round = true;
+            } else {
+                // соперников не осталось — обратно в разминку
+                round.phase = RoundPhase::Warmup;
+                round.phase_until = f64::INFINITY;
+                info!("⏸ Недостаточно игроков — разминка");
+            }
+        }
+    }
+
+    if start_round {
+        round.round += 1;
+        round.phase = RoundPhase::Freeze;
+        round.phase_until = now + ROUND_FREEZE_TIME;
+        info!("🧊 Раунд {}: заморозка", round.round);
+    }
+    true
+}
+
+/// Двигаем раунд и при его начале (или возврате в разминку) расставляем всех заново
+pub fn update_rounds(
+    mut round: ResMut<RoundState>,
+    teams: Res<Teams>,
+    team_spawns: Res<TeamSpawns>,
+    spawns: Res<SpawnPoints>,
+    mut states: ResMut<PlayerStates>,
+    mut spawned: ResMut<SpawnedClients>,
+    mut weapons: ResMut<WeaponStates>,
+    mut respawn_q: ResMut<RespawnQueue>,
+    mut damage_log: ResMut<DamageLog>,
+    mut server: ResMut<QuinnetServer>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs_f64();
+    let count = |team: Team| teams.0.values().filter(|&&t| t == team).count();
+    let both_teams = count(Team::Terrorists) > 0 && count(Team::CounterTerrorists) > 0;
+
+    let alive = |team: Team| {
+        teams
+            .0
+            .iter()
+            .filter(|&(&id, &t)| t == team && is_player_alive(id, &spawned.0, &states.0))
+            .count()
+    };
+    let alive = (alive(Team::Terrorists), alive(Team::CounterTerrorists));
+    if !advance_round(&mut round, now, both_teams, alive) {
+        return;
+    }
+
+    let endpoint = server.endpoint_mut();
+
+    // в начале раунда (и при возврате в разминку) — все живы, на своих точках, с полным боезапасом
+    if matches!(round.phase, RoundPhase::Freeze | RoundPhase::Warmup) {
+        respawn_q.0.clear();
+        // у всех снова 100 HP — старый урон на ассисты не идёт
+        damage_log.0.clear();
+        let mut per_team: [usize; 2] = [0, 0];
+        let mut ids: Vec<(u64, Team)> = teams.0.iter().map(|(&id, &t)| (id, t)).collect();
+        ids.sort_by_key(|&(id, _)| id);
+        for (id, team) in ids {
+            let index = &mut per_team[team as usize];
+            let pos = team_spawn_point(&team_spawns, &spawns, team, *index);
+            *index += 1;
+
+            states.0.insert(
+                id,
+                PlayerState {
+                    pos,
+                    hp: MAX_HP,
+                    ..Default::default()
+                },
+            );
+            spawned.0.insert(id);
+
+            let inventory = Inventory::default();
+            endpoint
+                .send_message_on(id, CH_S2C, inventory.ammo_msg())
+                .ok();
+            weapons.0.insert(id, inventory);
+
+            endpoint
+                .broadcast_message_on(
+                    CH_S2C,
+                    S2C::PlayerRespawn {
+                        id,
+                        x: pos.x,
+                        y: pos.y,
+                    },
+                )
+                .ok();
+        }
+    }
+
+    endpoint
+        .broadcast_message_on(CH_S2C, S2C::RoundState(round.info(now)))
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newcomer_joins_smaller_team() {
+        let mut teams = Teams::default();
+        assert_eq!(pick_team(&teams), Team::Terrorists);
+        teams.0.insert(1, Team::Terrorists);
+        assert_eq!(pick_team(&teams), Team::CounterTerrorists);
+        teams.0.insert(2, Team::CounterTerrorists);
+        assert_eq!(pick_team(&teams), Team::Terrorists);
+        teams.0.insert(3, Team::CounterTerrorists);
+        assert_eq!(pick_team(&teams), Team::Terrorists);
+    }
+
+    #[test]
+    fn round_goes_through_freeze_live_and_end() {
+        let mut round = RoundState::default();
+        // один на сервере — разминка
+        assert!(!advance_round(&mut round, 0.0, false, (1, 0)));
+        assert_eq!(round.phase, RoundPhase::Warmup);
+
+        assert!(advance_round(&mut round, 1.0, true, (1, 1)));
+        assert_eq!(round.phase, RoundPhase::Freeze);
+        assert_eq!(round.round, 1);
+        let live_at = 1.0 + ROUND_FREEZE_TIME;
+        assert!(!advance_round(&mut round, live_at - 0.1, true, (1, 1)));
+        assert!(advance_round(&mut round, live_at, true, (1, 1)));
+        assert_eq!(round.phase, RoundPhase::Live);
+        assert!(!advance_round(&mut round, live_at + 1.0, true, (1, 1)));
+
+        // время вышло — раунд за CT
+        let end_at = live_at + ROUND_TIME;
+        assert!(advance_round(&mut round, end_at, true, (1, 1)));
+        assert_eq!(
+            round.phase,
+            RoundPhase::Ended {
+                winner: Some(Team::CounterTerrorists)
+            }
+        );
+        assert_eq!((round.score_t, round.score_ct), (0, 1));
+
+        let next_at = end_at + ROUND_END_TIME;
+        assert!(!advance_round(&mut round, next_at - 0.1, true, (1, 1)));
+        assert!(advance_round(&mut round, next_at, true, (1, 1)));
+        assert_eq!(round.phase, RoundPhase::Freeze);
+        assert_eq!(round.round, 2);
+    }
+
+    #[test]
+    fn last_team_standing_wins() {
+        let live = || RoundState {
+            phase: RoundPhase::Live,
+            phase_until: 100.0,
+            ..Default::default()
+        };
+
+        let mut round = live();
+        assert!(advance_round(&mut round, 10.0, true, (2, 0)));
+        assert_eq!(
+            round.phase,
+            RoundPhase::Ended {
+                winner: Some(Team::Terrorists)
+            }
+        );
+        assert_eq!((round.score_t, round.score_ct), (1, 0));
+
+        // погибли все разом — ничья, счёт не меняется
+        let mut round = live();
+        assert!(advance_round(&mut round, 10.0, true, (0, 0)));
+        assert_eq!(round.phase, RoundPhase::Ended { winner: None });
+        assert_eq!((round.score_t, round.score_ct), (0, 0));
+
+        // соперник ушёл — после паузы обратно в разминку
+        let until = round.phase_until;
+        assert!(advance_round(&mut round, until, false, (1, 0)));
+        assert_eq!(round.phase, RoundPhase::Warmup);
+    }
+}
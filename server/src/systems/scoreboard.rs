@@ -0,0 +1,41 @@
+use crate::resources::{Latencies, ScoreboardTimer, Scores, Teams};
+use bevy::prelude::*;
+use bevy_quinnet::server::QuinnetServer;
+use protocol::constants::CH_S2C;
+use protocol::messages::{S2C, ScoreEntry};
+
+/// Раз в SCOREBOARD_INTERVAL рассылаем всем таблицу счёта с пингом
+pub fn broadcast_scoreboard(
+    time: Res<Time>,
+    mut timer: ResMut<ScoreboardTimer>,
+    scores: Res<Scores>,
+    teams: Res<Teams>,
+    latencies: Res<Latencies>,
+    mut server: ResMut<QuinnetServer>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let mut entries: Vec<ScoreEntry> = scores
+        .0
+        .iter()
+        .map(|(&id, score)| ScoreEntry {
+            id,
+            team: teams.0.get(&id).copied(),
+            kills: score.kills,
+            deaths: score.deaths,
+            assists: score.assists,
+            ping_ms: latencies
+                .0
+                .get(&id)
+                .map_or(0, |rtt| (rtt * 1000.0).round() as u32),
+        })
+        .collect();
+    entries.sort_by_key(|e| e.id);
+
+    server
+        .endpoint_mut()
+        .broadcast_message_on(CH_S2C, S2C::Scoreboard(entries))
+        .ok();
+}
@@ -1,99 +1,144 @@
+use crate::{
+    resources::{
+        ActiveMap, AppliedSeqs, HandshakedClients, MapCollision, PendingInputs, PlayerState,
+        PlayerStates, RoundState, SentSnapshots, ServerTick, SnapshotAcks, SnapshotHistory, Teams,
+    },
+    utils::{is_visible_to, push_history},
+};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_quinnet::server::QuinnetServer;
 use protocol::{
-    constants::{CH_S2C, MOVE_SPEED, PLAYER_SIZE, TICK_DT},
-    messages::{PlayerSnapshot, WorldSnapshot, S2C},
-};
-use crate::{
-    resources::{AppliedSeqs, PendingInputs, PlayerStates, ServerTickTimer, SnapshotHistory}, systems::wall::Wall, utils::push_history
+    constants::{CH_S2C_UNRELIABLE, MAX_INPUTS_PER_TICK},
+    delta::encode_delta,
+    messages::{PlayerSnapshot, S2C, WorldSnapshot},
+    packed::PackedSnapshot,
 };
+use sim::{CollisionWorld, MoveState, step_player};
+use std::collections::HashMap;
 
-/// AABB intersection test between two rectangles
-fn aabb_intersect(min_a: Vec2, max_a: Vec2, min_b: Vec2, max_b: Vec2) -> bool {
-    !(max_a.x < min_b.x || min_a.x > max_b.x || max_a.y < min_b.y || min_a.y > max_b.y)
-}
-
-/// checks if position collides with any wall
-fn is_blocked(pos: Vec2, wall_q: &Query<(&Transform, &Sprite), With<Wall>>) -> bool {
-    let half = PLAYER_SIZE * 0.5;
-    let min_a = pos + Vec2::new(-half, -half);
-    let max_a = pos + Vec2::new( half,  half);
-    for (wt, sprite) in wall_q.iter() {
-        if let Some(size) = sprite.custom_size {
-            let half_w = size / 2.0;
-            let center = wt.translation.truncate();
-            let min_b  = center - half_w;
-            let max_b  = center + half_w;
-            if aabb_intersect(min_a, max_a, min_b, max_b) {
-                return true;
-            }
-        }
-    }
-    false
+// Получатели снапшотов: их базы для дельт и всё для отсева невидимых врагов
+#[derive(SystemParam)]
+pub struct Recipients<'w> {
+    pub handshaked: Res<'w, HandshakedClients>,
+    pub acks: Res<'w, SnapshotAcks>,
+    pub sent: ResMut<'w, SentSnapshots>,
+    pub teams: Res<'w, Teams>,
+    pub map: Res<'w, ActiveMap>,
 }
 
-/// Server tick: applies pending inputs with sliding wall collision, broadcasts snapshot, and records history
+/// Server tick (FixedUpdate, TICK_DT): applies pending inputs through the shared sim step,
+/// sends every client a tick-stamped delta snapshot of what it can see, and records history
 pub fn server_tick(
     time: Res<Time>,
-    mut timer: ResMut<ServerTickTimer>,
+    mut tick: ResMut<ServerTick>,
     mut states: ResMut<PlayerStates>,
     mut pending: ResMut<PendingInputs>,
     mut applied: ResMut<AppliedSeqs>,
     mut history: ResMut<SnapshotHistory>,
     mut server: ResMut<QuinnetServer>,
-    wall_q: Query<(&Transform, &Sprite), With<Wall>>,  // walls for collision
+    collision: Res<MapCollision>,
+    round: Res<RoundState>,
+    mut recipients: Recipients,
 ) {
-    if !timer.0.tick(time.delta()).just_finished() {
-        return;
-    }
+    tick.0 = tick.0.wrapping_add(1);
 
+    // каждый инпут — ровно один шаг симуляции, как в предсказании клиента
     for (&id, queue) in pending.0.iter_mut() {
-        if let Some(input) = queue.back() {
+        let n = queue.len().min(MAX_INPUTS_PER_TICK);
+        for input in queue.drain(..n) {
             let st = states.0.entry(id).or_default();
-            let mut dir = Vec2::ZERO;
-            if input.up    { dir.y += 1.; }
-            if input.down  { dir.y -= 1.; }
-            if input.left  { dir.x -= 1.; }
-            if input.right { dir.x += 1.; }
-            dir = dir.normalize_or_zero();
+            let current = MoveState {
+                pos: st.pos,
+                rot: st.rot,
+            };
+            // заморозка раунда: поворачиваться можно, ходить — нет
+            let next = if round.is_frozen() {
+                MoveState {
+                    rot: input.rotation,
+                    ..current
+                }
+            } else {
+                step_player(current, &input, &collision.0)
+            };
+            st.pos = next.pos;
+            st.rot = next.rot;
+            st.stance = input.stance.clone();
+            applied.0.insert(id, input.seq);
+        }
+    }
 
-            let current = st.pos;
-            let delta   = dir * MOVE_SPEED * TICK_DT;
-            let mut new = current;
+    let players = snapshot_players(&states.0);
+    let server_time = time.elapsed_secs_f64();
+    let half_size = recipients.map.0.half_size();
 
-            let proposed_x = Vec2::new(current.x + delta.x, current.y);
-            if !is_blocked(proposed_x, &wall_q) {
-                new.x = proposed_x.x;
-            }
-            let proposed_y = Vec2::new(new.x, current.y + delta.y);
-            if !is_blocked(proposed_y, &wall_q) {
-                new.y = proposed_y.y;
+    // снапшот собираем под получателя (его ack инпутов, только видимые ему игроки) и шлём
+    // дельтой к последнему подтверждённому им тику, пока тот хранится; иначе — полный
+    let endpoint = server.endpoint_mut();
+    for &client_id in recipients.handshaked.0.keys() {
+        let snapshot = WorldSnapshot {
+            players: visible_players(client_id, &players, &states.0, &recipients, &collision.0),
+            tick: tick.0,
+            server_time,
+            ack_seq: applied.0.get(&client_id).copied().unwrap_or(0),
+        };
+
+        let acked = recipients.acks.0.get(&client_id).copied();
+        let sent = recipients.sent.0.entry(client_id).or_default();
+        // всё старше подтверждённого клиенту как база уже не понадобится
+        if let Some(acked) = acked {
+            while sent.front().is_some_and(|s| s.tick < acked) {
+                sent.pop_front();
             }
-            st.pos = new;
+        }
+        let baseline = acked.and_then(|acked| sent.iter().find(|s| s.tick == acked));
 
-            st.rot    = input.rotation;
-            st.stance = input.stance.clone();
-            applied.0.insert(id, input.seq);
+        let delta = encode_delta(&snapshot, baseline);
+        let Some(packed) = PackedSnapshot::pack(&delta, half_size) else {
+            warn!("🚫 Снапшот для {client_id} не влезает в пакет — не отправлен");
+            continue;
+        };
+        endpoint
+            .send_message_on(client_id, CH_S2C_UNRELIABLE, S2C::Snapshot(packed))
+            .ok();
+
+        sent.push_back(snapshot);
+        if sent.len() > history.cap {
+            sent.pop_front();
         }
-        queue.clear();
     }
 
-    let snapshot = WorldSnapshot {
-        players: states.0.iter().map(|(&id, st)| PlayerSnapshot {
+    push_history(&mut history, tick.0, &states.0);
+}
+
+/// Игроки, которых `viewer` должен видеть в снапшоте (см. `is_visible_to`)
+fn visible_players(
+    viewer: u64,
+    players: &[PlayerSnapshot],
+    states: &HashMap<u64, PlayerState>,
+    recipients: &Recipients,
+    walls: &CollisionWorld,
+) -> Vec<PlayerSnapshot> {
+    players
+        .iter()
+        .filter(|p| {
+            let pos = Vec2::new(p.x, p.y);
+            is_visible_to(viewer, p.id, pos, states, &recipients.teams.0, walls)
+        })
+        .cloned()
+        .collect()
+}
+
+fn snapshot_players(states: &HashMap<u64, PlayerState>) -> Vec<PlayerSnapshot> {
+    states
+        .iter()
+        .map(|(&id, st)| PlayerSnapshot {
             id,
             x: st.pos.x,
             y: st.pos.y,
             rotation: st.rot,
             stance: st.stance.clone(),
             hp: st.hp,
-        }).collect(),
-        server_time: time.elapsed_secs_f64(),
-        last_input_seq: applied.0.clone(),
-    };
-
-    server.endpoint_mut()
-        .broadcast_message_on(CH_S2C, S2C::Snapshot(snapshot.clone()))
-        .unwrap();
-
-    push_history(&mut history, snapshot.server_time, &states.0);
-}
\ No newline at end of file
+        })
+        .collect()
+}
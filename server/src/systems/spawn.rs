@@ -1,10 +1,18 @@
 use crate::{
-    events::{ClientConnected, ClientDisconnected, PlayerRespawn},
-    resources::{ConnectedClients, PlayerState, PlayerStates, SpawnPoints, SpawnedClients},
+    config::{GameMode, ServerConfig},
+    events::{ClientConnected, ClientDisconnected, PlayerJoined, PlayerRespawn},
+    resources::{
+        AppliedSeqs, ConnectedClients, DamageLog, HandshakedClients, Inventory, LastHeard,
+        Latencies, Nicknames, PendingInputs, Pickups, PlayerState, PlayerStates, RoundState,
+        Scores, SentSnapshots, ShotSeqs, SnapshotAcks, SpawnPoints, SpawnedClients, TeamSpawns,
+        Teams, WeaponStates,
+    },
+    systems::rounds::{pick_team, team_spawn_point},
 };
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_quinnet::server::QuinnetServer;
-use protocol::constants::CH_S2C;
+use protocol::constants::{CH_S2C, MAX_HP};
 use protocol::messages::S2C;
 
 // todo загружать логику спавнов отдельно
@@ -24,7 +32,7 @@ use protocol::messages::S2C;
 
 
 // todo сделать рандом тут
-fn pick_spawn_point(spawns: &Res<SpawnPoints>, index_hint: u64) -> Vec2 {
+fn pick_spawn_point(spawns: &SpawnPoints, index_hint: u64) -> Vec2 {
     if spawns.0.is_empty() {
         return Vec2::ZERO;
     }
@@ -32,29 +40,117 @@ fn pick_spawn_point(spawns: &Res<SpawnPoints>, index_hint: u64) -> Vec2 {
     spawns.0[i]
 }
 
+/// Точка спавна игрока на текущей карте: у игрока с командой — зона команды, иначе общие
+/// точки. Одна на первый вход и на респавн после смерти
+pub fn spawn_point_for(
+    spawns: &SpawnPoints,
+    team_spawns: &TeamSpawns,
+    teams: &Teams,
+    id: u64,
+) -> Vec2 {
+    match teams.0.get(&id) {
+        Some(&team) => team_spawn_point(team_spawns, spawns, team, id as usize),
+        None => pick_spawn_point(spawns, id),
+    }
+}
+
+/// Транспортное подключение: игрока пока не создаём, ждём C2S::Hello
 pub fn process_client_connected(
     mut ev: EventReader<ClientConnected>,
     mut connected: ResMut<ConnectedClients>,
+) {
+    for ClientConnected(id) in ev.read() {
+        if connected.0.insert(*id) {
+            info!("🔌 Клиент {id} подключился, ждём Hello");
+        }
+    }
+}
+
+/// Рукопожатие пройдено — создаём состояние игрока и сообщаем всем
+pub fn process_player_joined(
+    mut ev: EventReader<PlayerJoined>,
+    connected: Res<ConnectedClients>,
     mut spawned: ResMut<SpawnedClients>,
     mut states: ResMut<PlayerStates>,
+    mut weapons: ResMut<WeaponStates>,
     mut server: ResMut<QuinnetServer>,
     spawns: Res<SpawnPoints>,
+    config: Res<ServerConfig>,
+    mut teams: ResMut<Teams>,
+    team_spawns: Res<TeamSpawns>,
+    round: Res<RoundState>,
+    mut scores: ResMut<Scores>,
+    nicknames: Res<Nicknames>,
+    pickups: Res<Pickups>,
+    time: Res<Time>,
 ) {
-    for ClientConnected(id) in ev.read() {
-        if !connected.0.insert(*id) {
+    for PlayerJoined(id) in ev.read() {
+        if !connected.0.contains(id) || states.0.contains_key(id) {
             continue;
         }
-        let pos = pick_spawn_point(&spawns, *id);
-        states.0.insert(
-            *id,
-            PlayerState {
-                pos,
-                rot: 0.0,
-                stance: Default::default(),
-                hp: 100,
-            },
-        );
-        spawned.0.insert(*id);
+        scores.0.entry(*id).or_default();
+
+        // ник новичка — всем (в раундах он может ещё не заспавниться), новичку — остальные
+        let nickname = nicknames.0.get(id).cloned().unwrap_or_default();
+        let endpoint = server.endpoint_mut();
+        endpoint
+            .broadcast_message_on(
+                CH_S2C,
+                S2C::PlayerName {
+                    id: *id,
+                    nickname: nickname.clone(),
+                },
+            )
+            .ok();
+        for (&other, other_nick) in nicknames.0.iter().filter(|&(other, _)| other != id) {
+            endpoint
+                .send_message_on(
+                    *id,
+                    CH_S2C,
+                    S2C::PlayerName {
+                        id: other,
+                        nickname: other_nick.clone(),
+                    },
+                )
+                .ok();
+        }
+        // что из предметов карты сейчас лежит
+        endpoint
+            .send_message_on(*id, CH_S2C, pickups.states_msg())
+            .ok();
+
+        let mut spawn_now = true;
+        if config.mode == GameMode::Rounds {
+            let team = pick_team(&teams);
+            teams.0.insert(*id, team);
+
+            let endpoint = server.endpoint_mut();
+            endpoint
+                .broadcast_message_on(CH_S2C, S2C::PlayerTeam { id: *id, team })
+                .ok();
+            // новичку — команды остальных и текущий раунд
+            for (&other, &other_team) in teams.0.iter().filter(|&(other, _)| other != id) {
+                endpoint
+                    .send_message_on(
+                        *id,
+                        CH_S2C,
+                        S2C::PlayerTeam {
+                            id: other,
+                            team: other_team,
+                        },
+                    )
+                    .ok();
+            }
+            let now = time.elapsed_secs_f64();
+            endpoint
+                .send_message_on(*id, CH_S2C, S2C::RoundState(round.info(now)))
+                .ok();
+            info!("🎽 Игрок {id} «{nickname}» в команде {team:?}");
+
+            // раунд идёт — появится в следующем, но о входе все узнают сразу
+            spawn_now = round.allows_respawn();
+        }
+        let pos = spawn_point_for(&spawns, &team_spawns, &teams, *id);
 
         server
             .endpoint_mut()
@@ -64,23 +160,89 @@ pub fn process_client_connected(
                     id: *id,
                     x: pos.x,
                     y: pos.y,
+                    nickname: nickname.clone(),
+                    spawned: spawn_now,
                 },
             )
             .unwrap();
+        if !spawn_now {
+            info!("⏳ Игрок {id} «{nickname}» ждёт следующего раунда");
+            continue;
+        }
+
+        states.0.insert(
+            *id,
+            PlayerState {
+                pos,
+                rot: 0.0,
+                stance: Default::default(),
+                hp: MAX_HP,
+                armor: 0,
+            },
+        );
+        spawned.0.insert(*id);
+
+        let inventory = Inventory::default();
+        let endpoint = server.endpoint_mut();
+        endpoint
+            .send_message_on(*id, CH_S2C, inventory.ammo_msg())
+            .ok();
+        weapons.0.insert(*id, inventory);
     }
 }
 
+// Всё, что сервер помнит о соединении клиента: пинг, подтверждения, очередь инпутов
+#[derive(SystemParam)]
+pub struct ClientLinks<'w> {
+    pub last_heard: ResMut<'w, LastHeard>,
+    pub latencies: ResMut<'w, Latencies>,
+    pub snapshot_acks: ResMut<'w, SnapshotAcks>,
+    pub sent_snapshots: ResMut<'w, SentSnapshots>,
+    pub shot_seqs: ResMut<'w, ShotSeqs>,
+    pub pending: ResMut<'w, PendingInputs>,
+    pub applied: ResMut<'w, AppliedSeqs>,
+}
+
+impl ClientLinks<'_> {
+    fn forget(&mut self, id: u64) {
+        self.last_heard.0.remove(&id);
+        self.latencies.0.remove(&id);
+        self.snapshot_acks.0.remove(&id);
+        self.sent_snapshots.0.remove(&id);
+        self.shot_seqs.0.remove(&id);
+        self.pending.0.remove(&id);
+        self.applied.0.remove(&id);
+    }
+}
+
+/// Отключение по транспорту или C2S::Goodbye — одна и та же уборка
 pub fn process_client_disconnected(
     mut ev: EventReader<ClientDisconnected>,
     mut connected: ResMut<ConnectedClients>,
+    mut handshaked: ResMut<HandshakedClients>,
     mut spawned: ResMut<SpawnedClients>,
     mut states: ResMut<PlayerStates>,
+    mut weapons: ResMut<WeaponStates>,
+    mut teams: ResMut<Teams>,
+    mut scores: ResMut<Scores>,
+    mut links: ClientLinks,
+    mut damage_log: ResMut<DamageLog>,
+    mut nicknames: ResMut<Nicknames>,
     mut server: ResMut<QuinnetServer>,
 ) {
     for ClientDisconnected(id) in ev.read() {
         connected.0.remove(id);
+        handshaked.0.remove(id);
         spawned.0.remove(id);
         states.0.remove(id);
+        weapons.0.remove(id);
+        teams.0.remove(id);
+        scores.0.remove(id);
+        links.forget(*id);
+        damage_log.0.remove(id);
+        if let Some(nickname) = nicknames.0.remove(id) {
+            info!("🔌 Игрок {id} «{nickname}» отключился");
+        }
 
         server
             .endpoint_mut()
@@ -93,16 +255,25 @@ pub fn process_player_respawn(
     mut ev: EventReader<PlayerRespawn>,
     mut spawned: ResMut<SpawnedClients>,
     mut states: ResMut<PlayerStates>,
+    mut weapons: ResMut<WeaponStates>,
     mut server: ResMut<QuinnetServer>,
 ) {
     for PlayerRespawn { id, x, y } in ev.read() {
         spawned.0.insert(*id);
         let st = states.0.entry(*id).or_default();
         st.pos = Vec2::new(*x, *y);
-        st.hp = 100;
+        st.hp = MAX_HP;
+        st.armor = 0;
 
-        server
-            .endpoint_mut()
+        // после смерти — полный магазин и запас
+        let inventory = Inventory::default();
+        let endpoint = server.endpoint_mut();
+        endpoint
+            .send_message_on(*id, CH_S2C, inventory.ammo_msg())
+            .ok();
+        weapons.0.insert(*id, inventory);
+
+        endpoint
             .broadcast_message_on(
                 CH_S2C,
                 S2C::PlayerRespawn {
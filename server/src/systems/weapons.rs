@@ -0,0 +1,28 @@
+use crate::resources::WeaponStates;
+use bevy::prelude::*;
+use bevy_quinnet::server::QuinnetServer;
+use protocol::constants::CH_S2C;
+
+/// Завершаем перезарядки, у которых истёк таймер, и сообщаем владельцу патроны
+pub fn finish_reloads(
+    mut weapons: ResMut<WeaponStates>,
+    mut server: ResMut<QuinnetServer>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs_f64();
+    let endpoint = server.endpoint_mut();
+
+    for (&id, inventory) in weapons.0.iter_mut() {
+        let Some(until) = inventory.reload_until else {
+            continue;
+        };
+        if now < until {
+            continue;
+        }
+        inventory.finish_reload();
+        endpoint
+            .send_message_on(id, CH_S2C, inventory.ammo_msg())
+            .ok();
+        debug!("🔄 Игрок {id} перезарядил {}", inventory.active_def().name);
+    }
+}
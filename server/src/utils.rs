@@ -1,45 +1,193 @@
-use crate::constants::{HITBOX_RADIUS, MAX_RAY_LEN};
+use crate::constants::{
+    ARMOR_ABSORB, HEALTH_PACK_HP, LOS_CULL_MARGIN, MAX_ARMOR, MAX_HP, MAX_PENDING_INPUTS,
+    NICKNAME_MAX_LEN,
+};
 use crate::resources::PlayerState;
-use crate::systems::wall::Wall;
 use bevy::prelude::*;
-use protocol::messages::ShootEvent;
-use std::collections::{HashMap, VecDeque};
+use protocol::map::Tile;
+use protocol::messages::{InputState, ShootEvent, Team};
+use protocol::weapons::{ShotSpread, WeaponDef, pellet_dirs};
+use sim::CollisionWorld;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 /// Сохраняем историю состояний
 pub fn push_history(
     history: &mut crate::resources::SnapshotHistory,
-    now: f64,
+    tick: u32,
     states: &HashMap<u64, PlayerState>,
 ) {
-    history.buf.push_back((now, states.clone()));
+    history.buf.push_back((tick, states.clone()));
     if history.buf.len() > history.cap {
         history.buf.pop_front();
     }
 }
 
-// --- ВАША ФУНКЦИЯ С ДОБАВЛЕННЫМ LOS ---
+/// Ставим инпут в очередь клиента. Уже применённые и повторы (seq не новее последнего)
+/// отбрасываем, а при переполнении выкидываем самые старые — очередь и задержка не растут
+pub fn queue_input(queue: &mut VecDeque<InputState>, input: InputState, applied: Option<u32>) {
+    let newest = queue.back().map(|last| last.seq).or(applied);
+    if newest.is_some_and(|seq| input.seq <= seq) {
+        return;
+    }
+    queue.push_back(input);
+    while queue.len() > MAX_PENDING_INPUTS {
+        queue.pop_front();
+    }
+}
+
+/// Сверяем номер выстрела со счётчиком сервера; Err — ожидаемый номер. Сдвигаем счётчик
+/// только при совпадении: иначе клиент, выкинув пару выстрелов, выбрал бы себе сид разброса.
+/// Надёжный упорядоченный канал номер честного клиента не пропустит
+pub fn next_shot_seq(seqs: &mut HashMap<u64, u32>, client_id: u64, seq: u32) -> Result<(), u32> {
+    let expected = seqs.get(&client_id).map_or(1, |last| last.wrapping_add(1));
+    if seq != expected {
+        return Err(expected);
+    }
+    seqs.insert(client_id, seq);
+    Ok(())
+}
+
+/// Может ли клиент сейчас действовать (стрелять, бросать): только живой и заспавненный
+pub fn is_player_alive(
+    client_id: u64,
+    spawned: &HashSet<u64>,
+    states: &HashMap<u64, PlayerState>,
+) -> bool {
+    spawned.contains(&client_id) && states.get(&client_id).is_some_and(|st| st.hp > 0)
+}
+
+/// Урон по своему в режиме раундов не проходит (команды есть только там);
+/// по себе — своей же гранатой — проходит
+pub fn is_friendly_fire(teams: &HashMap<u64, Team>, source: Option<u64>, target: u64) -> bool {
+    let Some(src) = source.filter(|&src| src != target) else {
+        return false;
+    };
+    teams
+        .get(&src)
+        .is_some_and(|t| teams.get(&target) == Some(t))
+}
+
+/// Урон с учётом брони: (сколько снять с HP, сколько с брони).
+/// Броня берёт на себя долю ARMOR_ABSORB, но не больше, чем её осталось
+pub fn split_damage(amount: i32, armor: i32) -> (i32, i32) {
+    let absorbed = ((amount as f32 * ARMOR_ABSORB) as i32).clamp(0, armor.max(0));
+    (amount - absorbed, absorbed)
+}
+
+/// Подбираем предмет; false — не нужен (HP или броня уже полные), остаётся лежать
+pub fn apply_pickup(tile: Tile, st: &mut PlayerState) -> bool {
+    match tile {
+        Tile::HealthPack if st.hp < MAX_HP => {
+            st.hp = (st.hp + HEALTH_PACK_HP).min(MAX_HP);
+            true
+        }
+        Tile::ArmorVest if st.armor < MAX_ARMOR => {
+            st.armor = MAX_ARMOR;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Ограничиваем присланное клиентом значение диапазоном [0, max]; NaN/inf → max
+pub fn clamp_client_value(v: f32, max: f32) -> f32 {
+    if v.is_finite() {
+        v.clamp(0.0, max)
+    } else {
+        max
+    }
+}
+
+/// Ник из Hello: обрезаем пробелы, проверяем длину, символы и что он не занят (без учёта регистра)
+pub fn validate_nickname<'a>(
+    raw: &str,
+    taken: impl IntoIterator<Item = &'a String>,
+) -> Result<String, String> {
+    let nick = raw.trim();
+    if nick.is_empty() {
+        return Err("пустой ник".into());
+    }
+    if nick.chars().count() > NICKNAME_MAX_LEN {
+        return Err(format!("ник длиннее {NICKNAME_MAX_LEN} символов"));
+    }
+    if nick.chars().any(char::is_control) {
+        return Err("недопустимые символы в нике".into());
+    }
+    let lower = nick.to_lowercase();
+    if taken.into_iter().any(|other| other.to_lowercase() == lower) {
+        return Err(format!("ник «{nick}» уже занят"));
+    }
+    Ok(nick.to_string())
+}
+
+/// Тик, который клиент рисовал при выстреле, в пределах отката: не из будущего
+/// и не дальше `max_rewind` тиков назад. Выстрел из будущего отклоняем, слишком старый
+/// прижимаем к пределу. Возвращает событие с исправленным тиком или причину отказа
+pub fn clamp_rewind(
+    shoot: &ShootEvent,
+    now_tick: u32,
+    max_rewind: u32,
+) -> Result<ShootEvent, String> {
+    if !shoot.tick_frac.is_finite() || !shoot.dir.is_finite() {
+        return Err("битый выстрел".into());
+    }
+    if shoot.tick > now_tick {
+        return Err(format!(
+            "тик {} из будущего (сейчас {now_tick})",
+            shoot.tick
+        ));
+    }
+    let oldest = now_tick.saturating_sub(max_rewind);
+    if shoot.tick < oldest {
+        return Ok(ShootEvent {
+            tick: oldest,
+            tick_frac: 0.0,
+            ..shoot.clone()
+        });
+    }
+    Ok(shoot.clone())
+}
+
+/// Шёл ли игрок между двумя последними тиками истории
+pub fn moved_last_tick(history: &VecDeque<(u32, HashMap<u64, PlayerState>)>, id: u64) -> bool {
+    let mut recent = history
+        .iter()
+        .rev()
+        .filter_map(|(_, states)| states.get(&id));
+    match (recent.next(), recent.next()) {
+        (Some(a), Some(b)) => a.pos.distance_squared(b.pos) > 1e-6,
+        _ => false,
+    }
+}
+
+/// Лаг-компенсированный хитскан. `shooter_id` — id соединения, с которого пришёл выстрел,
+/// `shot` — разброс и отдача, `los_blocked` — проверка, перекрыт ли отрезок стенами.
+/// Возвращает по цели на каждый попавший луч (у дробовика их несколько).
 pub fn check_hit_lag_comp(
-    history: &std::collections::VecDeque<(f64, std::collections::HashMap<u64, PlayerState>)>,
-    current: &std::collections::HashMap<u64, PlayerState>,
+    history: &VecDeque<(u32, HashMap<u64, PlayerState>)>,
+    shooter_id: u64,
     shoot: &ShootEvent,
-    wall_q: &Query<(&Transform, &Sprite), With<Wall>>,        // ← НОВЫЙ ПАРАМЕТР
-) -> Option<u64> {
-    // Находим два снапшота вокруг shoot.timestamp
+    weapon: &WeaponDef,
+    shot: &ShotSpread,
+    los_blocked: impl Fn(Vec2, Vec2) -> bool,
+) -> Vec<u64> {
+    // Находим два тика вокруг того, что рисовал клиент
+    let target = shoot.tick as f64 + shoot.tick_frac.clamp(0.0, 1.0) as f64;
     let mut prev = None;
     let mut next = None;
     for (t, states) in history {
-        if *t <= shoot.timestamp {
-            prev = Some((t, states));
+        if *t as f64 <= target {
+            prev = Some((*t as f64, states));
         } else if next.is_none() {
-            next = Some((t, states));
+            next = Some((*t as f64, states));
         }
     }
     let (t0, s0, t1, s1) = match (prev, next) {
-        (Some((t0, s0)), Some((t1, s1))) => (*t0, s0, *t1, s1),
-        (Some((t0, s0)), None) => (*t0, s0, *t0, s0),
-        _ => return None,
+        (Some((t0, s0)), Some((t1, s1))) => (t0, s0, t1, s1),
+        (Some((t0, s0)), None) => (t0, s0, t0, s0),
+        _ => return Vec::new(),
     };
-    let alpha = ((shoot.timestamp - t0) / (t1 - t0).max(1e-4)).clamp(0.0, 1.0) as f32;
+    let alpha = ((target - t0) / (t1 - t0).max(1e-4)).clamp(0.0, 1.0) as f32;
 
     // интерполируем все позиции
     let mut interp: std::collections::HashMap<u64, PlayerState> = std::collections::HashMap::new();
@@ -54,102 +202,415 @@ pub fn check_hit_lag_comp(
                     rot: lerped_rot,
                     stance: p1.stance.clone(),
                     hp: p1.hp,
+                    armor: p1.armor,
                 },
             );
         }
     }
 
-    // луч из позиции стрелка (в интерполированном снапе)
-    let shooter = interp.get(&shoot.shooter_id)?;
-    let dir = shoot.dir.normalize_or_zero();
+    // лучи из позиции стрелка (в интерполированном снапе)
+    let Some(shooter) = interp.get(&shooter_id) else {
+        return Vec::new();
+    };
+
+    pellet_dirs(shoot.dir, weapon, shot)
+        .into_iter()
+        .filter_map(|dir| {
+            ray_first_hit(
+                &interp,
+                shooter_id,
+                shooter.pos,
+                dir,
+                weapon.range,
+                &los_blocked,
+            )
+        })
+        .collect()
+}
+
+/// Ближайшая к стрелку цель на луче (дробина не прошивает игроков насквозь)
+fn ray_first_hit(
+    players: &HashMap<u64, PlayerState>,
+    shooter_id: u64,
+    origin: Vec2,
+    dir: Vec2,
+    range: f32,
+    los_blocked: &impl Fn(Vec2, Vec2) -> bool,
+) -> Option<u64> {
+    let mut best: Option<(u64, f32)> = None;
 
-    for (&id, target) in interp.iter() {
-        if id == shoot.shooter_id {
+    for (&id, target) in players.iter() {
+        if id == shooter_id {
             continue;
         }
-        let to_target = target.pos - shooter.pos;
+        let to_target = target.pos - origin;
         // скалярная проекция на нормализованный dir
         let proj_len = to_target.dot(dir);
         if proj_len < 0.0 { continue; } // позади
-        if proj_len > MAX_RAY_LEN { continue; }
+        if proj_len > range { continue; }
 
         // ближайшая точка луча к центру цели
-        let nearest = shooter.pos + dir * proj_len;
+        let nearest = origin + dir * proj_len;
 
+        // присевший и лежащий — цель меньше
+        let radius = target.stance.hitbox_radius();
         // радиальное расстояние от центра цели до луча
-        if to_target.length() > 0.0 && to_target.distance(dir * proj_len) <= HITBOX_RADIUS {
+        if to_target.length() > 0.0 && to_target.distance(dir * proj_len) <= radius {
+            if best.is_some_and(|(_, d)| d <= proj_len) {
+                continue;
+            }
             // NEW: проверяем видимость до ближайшей точки попадания (а не до центра)
-            if los_blocked_by_walls(shooter.pos, nearest, wall_q) {
+            if los_blocked(origin, nearest) {
                 continue; // стена закрывает — не считаем попаданием
             }
-            return Some(id);
+            best = Some((id, proj_len));
         }
     }
-    None
+    best.map(|(id, _)| id)
+}
+
+pub fn los_blocked_by_walls(p0: Vec2, p1: Vec2, walls: &CollisionWorld) -> bool {
+    walls.segment_blocked(p0, p1)
 }
 
-fn los_blocked_by_walls(
-    p0: Vec2,
-    p1: Vec2,
-    wall_q: &Query<(&Transform, &Sprite), With<Wall>>,
+/// Видна ли `target` из `viewer`: хватает одного из лучей — в центр или в точки,
+/// сдвинутые поперёк луча на `margin`
+pub fn has_line_of_sight(viewer: Vec2, target: Vec2, margin: f32, walls: &CollisionWorld) -> bool {
+    let side = (target - viewer).perp().normalize_or_zero() * margin;
+    [target, target + side, target - side]
+        .into_iter()
+        .any(|p| !los_blocked_by_walls(viewer, p, walls))
+}
+
+/// Знает ли `viewer` об игроке `target` в точке `pos`: себя и союзников — всегда, врагов —
+/// только по лучу через стены (с запасом LOS_CULL_MARGIN); мёртвому врагов не показываем.
+/// Один фильтр на снапшоты и на всё, что выдаёт позицию игрока (выстрелы, лечение)
+pub fn is_visible_to(
+    viewer: u64,
+    target: u64,
+    pos: Vec2,
+    states: &HashMap<u64, PlayerState>,
+    teams: &HashMap<u64, Team>,
+    walls: &CollisionWorld,
 ) -> bool {
-    let eps = 0.001;
-    for (wt, sprite) in wall_q.iter() {
-        if let Some(size) = sprite.custom_size {
-            let half = size / 2.0 - Vec2::splat(eps);
-            let c = wt.translation.truncate();
-            let min = c - half;
-            let max = c + half;
-            if segment_aabb_intersect_t(p0, p1, min, max).is_some() {
-                return true;
-            }
-        }
-    }
-    false
+    viewer == target
+        || teams
+            .get(&viewer)
+            .is_some_and(|t| teams.get(&target) == Some(t))
+        || states
+            .get(&viewer)
+            .is_some_and(|st| has_line_of_sight(st.pos, pos, LOS_CULL_MARGIN, walls))
 }
 
-// ---------- Утилита: пересечение отрезка (луча) с AABB стены ----------
-#[inline]
-fn segment_aabb_intersect_t(p0: Vec2, p1: Vec2, min: Vec2, max: Vec2) -> Option<f32> {
-    // Liang–Barsky / slab
-    let d = p1 - p0;
-    let mut t0 = 0.0f32;
-    let mut t1 = 1.0f32;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::messages::Stance;
+    use protocol::weapons::WeaponKind;
 
-    if d.x.abs() < f32::EPSILON {
-        if p0.x < min.x || p0.x > max.x {
-            return None;
-        }
-    } else {
-        let inv = 1.0 / d.x;
-        let mut tmin = (min.x - p0.x) * inv;
-        let mut tmax = (max.x - p0.x) * inv;
-        if tmin > tmax {
-            core::mem::swap(&mut tmin, &mut tmax);
-        }
-        t0 = t0.max(tmin);
-        t1 = t1.min(tmax);
-        if t0 > t1 {
-            return None;
+    fn player(x: f32, y: f32) -> PlayerState {
+        PlayerState {
+            pos: Vec2::new(x, y),
+            hp: 100,
+            ..Default::default()
         }
     }
 
-    if d.y.abs() < f32::EPSILON {
-        if p0.y < min.y || p0.y > max.y {
-            return None;
-        }
-    } else {
-        let inv = 1.0 / d.y;
-        let mut tmin = (min.y - p0.y) * inv;
-        let mut tmax = (max.y - p0.y) * inv;
-        if tmin > tmax {
-            core::mem::swap(&mut tmin, &mut tmax);
-        }
-        t0 = t0.max(tmin);
-        t1 = t1.min(tmax);
-        if t0 > t1 {
-            return None;
+    // первый выстрел стоя на месте: отдачи нет, разброс минимальный
+    fn still() -> ShotSpread {
+        ShotSpread::default()
+    }
+
+    const ATTACKER: u64 = 1;
+    const VICTIM: u64 = 2;
+    const BYSTANDER: u64 = 3;
+
+    // жертва стоит прямо напротив третьего игрока, атакующий — в стороне
+    fn world() -> HashMap<u64, PlayerState> {
+        HashMap::from([
+            (ATTACKER, player(0.0, 500.0)),
+            (VICTIM, player(0.0, 0.0)),
+            (BYSTANDER, player(300.0, 0.0)),
+        ])
+    }
+
+    fn history() -> VecDeque<(u32, HashMap<u64, PlayerState>)> {
+        VecDeque::from([(1, world()), (2, world())])
+    }
+
+    #[test]
+    fn ray_starts_at_connection_owner() {
+        // луч вдоль +X попал бы в третьего игрока, если бы стрелял VICTIM
+        let shoot = ShootEvent {
+            dir: Vec2::X,
+            tick: 1,
+            tick_frac: 0.5,
+            ..Default::default()
+        };
+        let rifle = WeaponKind::Rifle.def();
+        assert_eq!(
+            check_hit_lag_comp(&history(), VICTIM, &shoot, rifle, &still(), |_, _| false),
+            vec![BYSTANDER]
+        );
+        // но выстрел пришёл с соединения ATTACKER — от его позиции луч мимо
+        assert!(
+            check_hit_lag_comp(&history(), ATTACKER, &shoot, rifle, &still(), |_, _| false)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn shooter_never_hits_himself() {
+        let shoot = ShootEvent {
+            dir: Vec2::NEG_Y,
+            tick: 1,
+            tick_frac: 0.5,
+            ..Default::default()
+        };
+        let rifle = WeaponKind::Rifle.def();
+        assert_eq!(
+            check_hit_lag_comp(&history(), ATTACKER, &shoot, rifle, &still(), |_, _| false),
+            vec![VICTIM]
+        );
+        assert!(
+            !check_hit_lag_comp(&history(), VICTIM, &shoot, rifle, &still(), |_, _| false)
+                .contains(&VICTIM)
+        );
+    }
+
+    #[test]
+    fn crouching_target_is_harder_to_hit() {
+        // луч проходит в 18 px от центра жертвы: стоящую задевает, присевшую уже нет
+        let shoot = ShootEvent {
+            dir: Vec2::new(18.0, -500.0).normalize(),
+            tick: 1,
+            tick_frac: 0.5,
+            ..Default::default()
+        };
+        // у снайперки с места разброса нет — луч идёт ровно по прицелу
+        let sniper = WeaponKind::Sniper.def();
+        assert_eq!(
+            check_hit_lag_comp(&history(), ATTACKER, &shoot, sniper, &still(), |_, _| false),
+            vec![VICTIM]
+        );
+        let mut crouched = world();
+        crouched.get_mut(&VICTIM).unwrap().stance = Stance::Crouching;
+        let history = VecDeque::from([(1, crouched.clone()), (2, crouched)]);
+        assert!(
+            check_hit_lag_comp(&history, ATTACKER, &shoot, sniper, &still(), |_, _| false)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn rewind_is_clamped_and_future_ticks_rejected() {
+        let shoot = |tick| ShootEvent {
+            dir: Vec2::X,
+            tick,
+            tick_frac: 0.5,
+            ..Default::default()
+        };
+        // в пределах окна — как прислал клиент
+        assert_eq!(clamp_rewind(&shoot(95), 100, 13).unwrap().tick, 95);
+        // слишком далеко назад — прижат к пределу
+        let clamped = clamp_rewind(&shoot(10), 100, 13).unwrap();
+        assert_eq!((clamped.tick, clamped.tick_frac), (87, 0.0));
+        // из будущего и с мусором вместо доли тика — отказ
+        assert!(clamp_rewind(&shoot(101), 100, 13).is_err());
+        let mut nan = shoot(95);
+        nan.tick_frac = f32::NAN;
+        assert!(clamp_rewind(&nan, 100, 13).is_err());
+    }
+
+    #[test]
+    fn shotgun_pellets_stop_at_first_target() {
+        // в упор все дробины попадают в ближнего, стоящий за ним не получает ничего
+        let close = HashMap::from([
+            (ATTACKER, player(0.0, 0.0)),
+            (VICTIM, player(60.0, 0.0)),
+            (BYSTANDER, player(120.0, 0.0)),
+        ]);
+        let history = VecDeque::from([(1, close.clone()), (2, close)]);
+        let shoot = ShootEvent {
+            dir: Vec2::X,
+            tick: 1,
+            tick_frac: 0.5,
+            ..Default::default()
+        };
+        let shotgun = WeaponKind::Shotgun.def();
+
+        let hits = check_hit_lag_comp(&history, ATTACKER, &shoot, shotgun, &still(), |_, _| false);
+        assert_eq!(hits, vec![VICTIM; shotgun.pellets as usize]);
+    }
+
+    #[test]
+    fn armor_absorbs_part_of_damage_until_depleted() {
+        assert_eq!(split_damage(40, 0), (40, 0));
+        assert_eq!(split_damage(40, 100), (20, 20));
+        // брони меньше, чем она взяла бы, — остальное по HP
+        assert_eq!(split_damage(40, 5), (35, 5));
+    }
+
+    #[test]
+    fn pickup_is_taken_only_when_needed() {
+        let mut st = player(0.0, 0.0);
+        assert!(
+            !apply_pickup(Tile::HealthPack, &mut st),
+            "полное HP — аптечка лежит"
+        );
+        st.hp = 70;
+        assert!(apply_pickup(Tile::HealthPack, &mut st));
+        assert_eq!(st.hp, MAX_HP);
+        assert!(apply_pickup(Tile::ArmorVest, &mut st));
+        assert!(!apply_pickup(Tile::ArmorVest, &mut st));
+        assert_eq!(st.armor, MAX_ARMOR);
+    }
+
+    #[test]
+    fn dead_or_unspawned_players_cannot_shoot() {
+        let mut states = world();
+        let spawned = HashSet::from([ATTACKER, VICTIM]);
+
+        assert!(is_player_alive(ATTACKER, &spawned, &states));
+        // не заспавнен
+        assert!(!is_player_alive(BYSTANDER, &spawned, &states));
+        // умер: состояние удалено
+        states.remove(&VICTIM);
+        assert!(!is_player_alive(VICTIM, &spawned, &states));
+        // hp <= 0, но ещё не вычищен
+        states.get_mut(&ATTACKER).unwrap().hp = 0;
+        assert!(!is_player_alive(ATTACKER, &spawned, &states));
+    }
+
+    #[test]
+    fn teammates_cannot_hurt_each_other() {
+        let teams = HashMap::from([
+            (ATTACKER, Team::Terrorists),
+            (VICTIM, Team::Terrorists),
+            (BYSTANDER, Team::CounterTerrorists),
+        ]);
+        assert!(is_friendly_fire(&teams, Some(ATTACKER), VICTIM));
+        assert!(!is_friendly_fire(&teams, Some(ATTACKER), BYSTANDER));
+        // своя граната и урон без источника проходят
+        assert!(!is_friendly_fire(&teams, Some(ATTACKER), ATTACKER));
+        assert!(!is_friendly_fire(&teams, None, VICTIM));
+        // в дезматче команд нет
+        assert!(!is_friendly_fire(&HashMap::new(), Some(ATTACKER), VICTIM));
+    }
+
+    #[test]
+    fn nickname_is_trimmed_and_validated() {
+        let none: [&String; 0] = [];
+        assert_eq!(validate_nickname("  Вася  ", none), Ok("Вася".to_string()));
+        assert!(validate_nickname("   ", none).is_err());
+        assert!(validate_nickname("", none).is_err());
+
+        let longest = "я".repeat(NICKNAME_MAX_LEN);
+        assert_eq!(validate_nickname(&longest, none), Ok(longest.clone()));
+        assert!(validate_nickname(&format!("{longest}я"), none).is_err());
+
+        assert!(validate_nickname("bad\nname", none).is_err());
+        assert!(validate_nickname("tab\tname", none).is_err());
+    }
+
+    #[test]
+    fn taken_nickname_is_rejected_ignoring_case() {
+        let taken = ["Вася".to_string(), "bob".to_string()];
+        assert!(validate_nickname("вася", &taken).is_err());
+        assert!(validate_nickname(" BOB ", &taken).is_err());
+        assert_eq!(validate_nickname("Петя", &taken), Ok("Петя".to_string()));
+    }
+
+    #[test]
+    fn input_queue_is_bounded_and_skips_stale_seqs() {
+        let input = |seq| InputState {
+            seq,
+            up: false,
+            down: false,
+            left: false,
+            right: false,
+            rotation: 0.0,
+            stance: Stance::Standing,
+            timestamp: 0.0,
+        };
+        let seqs = |q: &VecDeque<InputState>| q.iter().map(|i| i.seq).collect::<Vec<_>>();
+
+        let mut queue = VecDeque::new();
+        // 5 уже применён: он и всё старше — мимо
+        queue_input(&mut queue, input(4), Some(5));
+        queue_input(&mut queue, input(5), Some(5));
+        queue_input(&mut queue, input(6), Some(5));
+        // повтор и опоздавший из прошлого
+        queue_input(&mut queue, input(6), Some(5));
+        assert_eq!(seqs(&queue), [6]);
+
+        let last = 6 + MAX_PENDING_INPUTS as u32 * 2;
+        for seq in 7..=last {
+            queue_input(&mut queue, input(seq), Some(5));
         }
+        assert_eq!(queue.len(), MAX_PENDING_INPUTS);
+        assert_eq!(queue.back().unwrap().seq, last);
+        let oldest = last + 1 - MAX_PENDING_INPUTS as u32;
+        assert_eq!(queue.front().unwrap().seq, oldest);
+    }
+
+    // стена в клетке x = 5 от пола до y = 192, смотрящий стоит слева от неё
+    fn culling_world() -> (HashMap<u64, PlayerState>, CollisionWorld) {
+        let walls =
+            CollisionWorld::from_tiles(Vec2::ZERO, 10, 10, (0..6).map(|y| IVec2::new(5, y)));
+        (HashMap::from([(ATTACKER, player(16.0, 160.0))]), walls)
+    }
+
+    #[test]
+    fn enemy_behind_wall_is_culled_but_teammate_and_self_kept() {
+        let (states, walls) = culling_world();
+        let hidden = Vec2::new(300.0, 100.0);
+        let enemies = HashMap::from([
+            (ATTACKER, Team::Terrorists),
+            (VICTIM, Team::CounterTerrorists),
+        ]);
+        assert!(!is_visible_to(
+            ATTACKER, VICTIM, hidden, &states, &enemies, &walls
+        ));
+        let mates = HashMap::from([(ATTACKER, Team::Terrorists), (VICTIM, Team::Terrorists)]);
+        assert!(is_visible_to(
+            ATTACKER, VICTIM, hidden, &states, &mates, &walls
+        ));
+        assert!(is_visible_to(
+            ATTACKER, ATTACKER, hidden, &states, &enemies, &walls
+        ));
+    }
+
+    #[test]
+    fn peeking_enemy_is_kept_thanks_to_margin() {
+        // центр за краем стены, но край тела уже выглядывает
+        let (states, walls) = culling_world();
+        let peeking = Vec2::new(300.0, 200.0);
+        assert!(!has_line_of_sight(
+            states[&ATTACKER].pos,
+            peeking,
+            0.0,
+            &walls
+        ));
+        assert!(is_visible_to(
+            ATTACKER,
+            VICTIM,
+            peeking,
+            &states,
+            &HashMap::new(),
+            &walls
+        ));
+    }
+
+    #[test]
+    fn jumped_shot_seq_does_not_move_counter() {
+        let mut seqs = HashMap::new();
+        assert_eq!(next_shot_seq(&mut seqs, ATTACKER, 1), Ok(()));
+        // скачок вперёд отклонён и ничего не сдвигает — следующий всё равно №2
+        assert_eq!(next_shot_seq(&mut seqs, ATTACKER, 50), Err(2));
+        assert_eq!(next_shot_seq(&mut seqs, ATTACKER, 51), Err(2));
+        assert_eq!(next_shot_seq(&mut seqs, ATTACKER, 2), Ok(()));
+        // у другого клиента счёт свой
+        assert_eq!(next_shot_seq(&mut seqs, VICTIM, 2), Err(1));
     }
-    Some(t0.clamp(0.0, 1.0))
 }
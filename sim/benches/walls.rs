@@ -0,0 +1,109 @@
+//! Лучи и коллизии по сетке против перебора всех стен, как было на сервере.
+//! Запуск: `cargo bench -p sim --bench walls`
+use glam::Vec2;
+use protocol::constants::{PLAYER_SIZE, TILE_SIZE};
+use protocol::map::{DEFAULT_MAP, GameMap, Tile, map_path};
+use sim::CollisionWorld;
+use sim::collision::segment_aabb;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::Instant;
+
+const RAYS: usize = 20_000;
+
+/// Стены так, как их видел сервер: по AABB на клетку
+fn wall_boxes(map: &GameMap) -> Vec<(Vec2, Vec2)> {
+    let half = Vec2::splat(TILE_SIZE / 2.0 - 0.001);
+    map.tiles()
+        .filter(|&(_, _, tile)| tile == Tile::Wall)
+        .map(|(x, y, _)| {
+            let c = map.tile_center(x, y);
+            (c - half, c + half)
+        })
+        .collect()
+}
+
+fn brute_blocked(walls: &[(Vec2, Vec2)], p0: Vec2, p1: Vec2) -> bool {
+    walls
+        .iter()
+        .any(|&(min, max)| segment_aabb(p0, p1, min, max).is_some())
+}
+
+fn brute_overlaps(walls: &[(Vec2, Vec2)], center: Vec2, half: f32) -> bool {
+    walls.iter().any(|&(min, max)| {
+        center.x + half > min.x
+            && center.x - half < max.x
+            && center.y + half > min.y
+            && center.y - half < max.y
+    })
+}
+
+/// Пары точек по центрам пустых клеток: детерминированный LCG, без лишних зависимостей
+fn segments(map: &GameMap) -> Vec<(Vec2, Vec2)> {
+    let open: Vec<Vec2> = map
+        .tiles()
+        .filter(|&(_, _, tile)| tile != Tile::Wall)
+        .map(|(x, y, _)| map.tile_center(x, y))
+        .collect();
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = || {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        open[(seed >> 33) as usize % open.len()]
+    };
+    (0..RAYS).map(|_| (next(), next())).collect()
+}
+
+fn time_ns(f: impl FnOnce() -> usize) -> (f64, usize) {
+    let start = Instant::now();
+    let n = black_box(f());
+    (start.elapsed().as_secs_f64() * 1e9 / RAYS as f64, n)
+}
+
+fn main() {
+    // бенчмарк запускается из каталога крейта — карты берём по пути исходников
+    let maps = Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets/maps");
+    let map = GameMap::load(&map_path(&maps, DEFAULT_MAP)).expect("карта по умолчанию");
+    let walls = wall_boxes(&map);
+    let world = CollisionWorld::from_map(&map);
+    let segs = segments(&map);
+    println!(
+        "карта «{}» {}x{}, стен {}, отрезков {RAYS}",
+        map.name,
+        map.width,
+        map.height,
+        walls.len()
+    );
+
+    let (brute_ns, brute_hits) = time_ns(|| {
+        segs.iter()
+            .filter(|&&(a, b)| brute_blocked(&walls, black_box(a), b))
+            .count()
+    });
+    let (grid_ns, grid_hits) = time_ns(|| {
+        segs.iter()
+            .filter(|&&(a, b)| world.segment_blocked(black_box(a), b))
+            .count()
+    });
+    assert_eq!(brute_hits, grid_hits, "сетка и перебор разошлись");
+    println!(
+        "линия видимости: перебор {brute_ns:.0} нс, сетка {grid_ns:.0} нс ({:.1}x), перекрыто {grid_hits}",
+        brute_ns / grid_ns
+    );
+
+    let half = PLAYER_SIZE / 2.0;
+    let (brute_ns, brute_hits) = time_ns(|| {
+        segs.iter()
+            .filter(|&&(a, _)| brute_overlaps(&walls, black_box(a), half))
+            .count()
+    });
+    let (grid_ns, grid_hits) = time_ns(|| {
+        segs.iter()
+            .filter(|&&(a, _)| world.is_blocked(black_box(a), half))
+            .count()
+    });
+    assert_eq!(brute_hits, grid_hits, "сетка и перебор разошлись");
+    println!(
+        "коллизия игрока: перебор {brute_ns:.0} нс, сетка {grid_ns:.0} нс ({:.1}x)",
+        brute_ns / grid_ns
+    );
+}
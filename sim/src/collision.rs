@@ -0,0 +1,283 @@
+use glam::{IVec2, Vec2};
+use protocol::constants::{SEPARATION_EPS, TILE_SIZE};
+use protocol::map::{GameMap, Tile, merge_rects};
+
+// стена для лучей чуть меньше клетки: луч вдоль грани не цепляет соседнюю
+const WALL_EPS: f32 = 0.001;
+
+/// Сетка непроходимых клеток карты в мировых координатах; все запросы смотрят
+/// только клетки рядом с телом или под лучом, а не все стены карты.
+/// Клетки стен слиты в прямоугольники: круг и луч сталкиваются с целым блоком, без швов
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CollisionWorld {
+    /// мировая точка левого нижнего угла клетки (0, 0)
+    origin: Vec2,
+    width: usize,
+    height: usize,
+    /// индекс прямоугольника стены для каждой клетки
+    cells: Vec<Option<u32>>,
+    /// (min, max) в мировых координатах
+    rects: Vec<(Vec2, Vec2)>,
+}
+
+impl CollisionWorld {
+    pub fn from_map(map: &GameMap) -> Self {
+        let solid = map.tiles().map(|(_, _, tile)| tile == Tile::Wall).collect();
+        Self::from_grid(-map.half_size(), map.width, map.height, solid)
+    }
+
+    /// Из готового списка клеток стен (x, y), как `SolidTiles` на сервере
+    pub fn from_tiles(
+        origin: Vec2,
+        width: usize,
+        height: usize,
+        tiles: impl IntoIterator<Item = IVec2>,
+    ) -> Self {
+        let mut solid = vec![false; width * height];
+        for t in tiles {
+            if t.x >= 0 && t.y >= 0 && (t.x as usize) < width && (t.y as usize) < height {
+                solid[t.y as usize * width + t.x as usize] = true;
+            }
+        }
+        Self::from_grid(origin, width, height, solid)
+    }
+
+    fn from_grid(origin: Vec2, width: usize, height: usize, solid: Vec<bool>) -> Self {
+        let mut cells = vec![None; width * height];
+        let rects = merge_rects(width, height, |x, y| solid[y * width + x])
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| {
+                for y in r.y..r.y + r.h {
+                    cells[y * width + r.x..y * width + r.x + r.w].fill(Some(i as u32));
+                }
+                let min = origin + Vec2::new(r.x as f32, r.y as f32) * TILE_SIZE;
+                (min, min + r.size())
+            })
+            .collect();
+        Self {
+            origin,
+            width,
+            height,
+            cells,
+            rects,
+        }
+    }
+
+    /// Прямоугольник стены, в который входит клетка; за пределами карты стен нет
+    fn rect_at(&self, x: i32, y: i32) -> Option<(Vec2, Vec2)> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        let i = self.cells[y as usize * self.width + x as usize]?;
+        Some(self.rects[i as usize])
+    }
+
+    /// Клетка стены; за пределами карты стен нет
+    pub fn is_solid(&self, x: i32, y: i32) -> bool {
+        self.rect_at(x, y).is_some()
+    }
+
+    /// Квадрат с центром `center` заходит в стену; касание стеной не считается
+    pub fn is_blocked(&self, center: Vec2, half: f32) -> bool {
+        let min = ((center - half - self.origin) / TILE_SIZE).floor();
+        let max = ((center + half - self.origin) / TILE_SIZE).ceil();
+        for ty in min.y as i32..max.y as i32 {
+            for tx in min.x as i32..max.x as i32 {
+                if self.is_solid(tx, ty) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Первое пересечение отрезка со стеной — доля пути от `p0` в [0, 1].
+    /// Клетки обходим по порядку вдоль отрезка (Amanatides–Woo), первая попавшаяся и есть ближайшая
+    pub fn raycast(&self, p0: Vec2, p1: Vec2) -> Option<f32> {
+        let a = (p0 - self.origin) / TILE_SIZE;
+        let d = (p1 - p0) / TILE_SIZE;
+        let mut cell = a.floor().as_ivec2();
+        let step = IVec2::new(
+            if d.x < 0.0 { -1 } else { 1 },
+            if d.y < 0.0 { -1 } else { 1 },
+        );
+        // доля пути до ближайшей границы клеток по оси и между соседними границами
+        let axis = |a: f32, d: f32, cell: i32| {
+            if d > 0.0 {
+                (((cell + 1) as f32 - a) / d, 1.0 / d)
+            } else if d < 0.0 {
+                ((cell as f32 - a) / d, -1.0 / d)
+            } else {
+                (f32::INFINITY, f32::INFINITY)
+            }
+        };
+        let (mut next_x, delta_x) = axis(a.x, d.x, cell.x);
+        let (mut next_y, delta_y) = axis(a.y, d.y, cell.y);
+
+        loop {
+            // вход в весь блок: внутри него до этой клетки других стен нет
+            if let Some((min, max)) = self.rect_at(cell.x, cell.y)
+                && let Some(t) = segment_aabb(p0, p1, min + WALL_EPS, max - WALL_EPS)
+            {
+                return Some(t);
+            }
+            if next_x.min(next_y) > 1.0 {
+                return None;
+            }
+            if next_x < next_y {
+                cell.x += step.x;
+                next_x += delta_x;
+            } else {
+                cell.y += step.y;
+                next_y += delta_y;
+            }
+        }
+    }
+
+    /// Отрезок упирается в стену (линия видимости перекрыта)
+    pub fn segment_blocked(&self, p0: Vec2, p1: Vec2) -> bool {
+        self.raycast(p0, p1).is_some()
+    }
+
+    /// Круг против стен: (наружная нормаль, минимальная поправка центра) для ближайшего блока
+    pub fn collide_circle(&self, center: Vec2, r: f32) -> Option<(Vec2, Vec2)> {
+        let min = ((center - r - self.origin) / TILE_SIZE).floor();
+        let max = ((center + r - self.origin) / TILE_SIZE).floor();
+        let mut best: Option<(f32, Vec2, Vec2, Vec2)> = None;
+        for ty in min.y as i32..=max.y as i32 {
+            for tx in min.x as i32..=max.x as i32 {
+                // блок из нескольких клеток проверится несколько раз — это дешевле, чем помнить
+                let Some((rect_min, rect_max)) = self.rect_at(tx, ty) else {
+                    continue;
+                };
+                let closest = center.clamp(rect_min, rect_max);
+                let d2 = center.distance_squared(closest);
+                // на стыке двух блоков берём грань, а не угол соседнего
+                if d2 <= r * r && best.is_none_or(|(b, ..)| d2 < b) {
+                    best = Some((d2, rect_min, rect_max, closest));
+                }
+            }
+        }
+        let (d2, tile_min, tile_max, closest) = best?;
+
+        if d2 > 0.0 {
+            let dist = d2.sqrt();
+            let n = (center - closest) / dist;
+            return Some((n, n * (r - dist + SEPARATION_EPS)));
+        }
+
+        // центр уже внутри блока — выталкиваем по оси с наименьшим проникновением
+        let pen = [
+            (center.x - tile_min.x, Vec2::NEG_X),
+            (tile_max.x - center.x, Vec2::X),
+            (center.y - tile_min.y, Vec2::NEG_Y),
+            (tile_max.y - center.y, Vec2::Y),
+        ];
+        let (depth, n) = pen
+            .into_iter()
+            .fold(pen[0], |a, b| if b.0 < a.0 { b } else { a });
+        Some((n, n * (depth + r + SEPARATION_EPS)))
+    }
+}
+
+/// Доля пути, на которой отрезок входит в AABB (Liang–Barsky); None — не пересекает
+pub fn segment_aabb(p0: Vec2, p1: Vec2, min: Vec2, max: Vec2) -> Option<f32> {
+    let d = p1 - p0;
+    let mut t0 = 0.0f32;
+    let mut t1 = 1.0f32;
+    for (p, d, min, max) in [(p0.x, d.x, min.x, max.x), (p0.y, d.y, min.y, max.y)] {
+        if d.abs() < f32::EPSILON {
+            if p < min || p > max {
+                return None;
+            }
+            continue;
+        }
+        let inv = 1.0 / d;
+        let (tmin, tmax) = {
+            let (a, b) = ((min - p) * inv, (max - p) * inv);
+            if a > b { (b, a) } else { (a, b) }
+        };
+        t0 = t0.max(tmin);
+        t1 = t1.min(tmax);
+        if t0 > t1 {
+            return None;
+        }
+    }
+    Some(t0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const MAP: &str = "\
+##########
+#S.......#
+#..##....#
+#..##..#.#
+#......#.#
+#.#......#
+#........#
+##########
+";
+
+    fn world() -> (GameMap, CollisionWorld) {
+        let map = GameMap::parse("test", MAP).unwrap();
+        let world = CollisionWorld::from_map(&map);
+        (map, world)
+    }
+
+    /// Старый способ: перебор всех стен карты
+    fn brute_force(map: &GameMap, p0: Vec2, p1: Vec2) -> Option<f32> {
+        map.tiles()
+            .filter(|&(_, _, tile)| tile == Tile::Wall)
+            .filter_map(|(x, y, _)| {
+                let half = Vec2::splat(TILE_SIZE / 2.0 - WALL_EPS);
+                let c = map.tile_center(x, y);
+                segment_aabb(p0, p1, c - half, c + half)
+            })
+            .min_by(f32::total_cmp)
+    }
+
+    #[test]
+    fn from_tiles_matches_from_map() {
+        let (map, world) = world();
+        let tiles = map
+            .tiles()
+            .filter(|&(_, _, tile)| tile == Tile::Wall)
+            .map(|(x, y, _)| IVec2::new(x as i32, y as i32));
+        let built = CollisionWorld::from_tiles(-map.half_size(), map.width, map.height, tiles);
+        assert_eq!(built, world);
+    }
+
+    #[test]
+    fn ray_along_wall_face_is_clear() {
+        let (map, world) = world();
+        // вдоль нижней грани верхней стены, от края до края
+        let y = map.tile_center(0, 7).y - TILE_SIZE / 2.0;
+        let from = Vec2::new(map.tile_center(1, 0).x, y);
+        let to = Vec2::new(map.tile_center(8, 0).x, y);
+        assert!(!world.segment_blocked(from, to));
+        // а сквозь колонну — нет
+        assert!(world.segment_blocked(map.tile_center(1, 2), map.tile_center(6, 3)));
+    }
+
+    proptest! {
+        #[test]
+        fn raycast_matches_brute_force(
+            x0 in -200.0f32..200.0, y0 in -150.0f32..150.0,
+            x1 in -200.0f32..200.0, y1 in -150.0f32..150.0,
+        ) {
+            let (map, world) = world();
+            let (p0, p1) = (Vec2::new(x0, y0), Vec2::new(x1, y1));
+            let grid = world.raycast(p0, p1);
+            let brute = brute_force(&map, p0, p1);
+            prop_assert_eq!(grid.is_some(), brute.is_some());
+            if let (Some(g), Some(b)) = (grid, brute) {
+                prop_assert!((g - b).abs() < 1e-4, "grid {} brute {}", g, b);
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use glam::Vec2;
+use protocol::constants::{
+    GRENADE_BOUNCE_DAMPING, GRENADE_DRAG_PER_TICK, GRENADE_RADIUS, GRENADE_RESTITUTION,
+    GRENADE_STOP_SPEED, MAX_STEP, TICK_DT,
+};
+
+use crate::collision::CollisionWorld;
+
+/// Положение и скорость гранаты в мировых координатах
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GrenadeBody {
+    pub pos: Vec2,
+    pub vel: Vec2,
+}
+
+/// Один тик (TICK_DT) полёта: сопротивление воздуха, перенос подшагами MAX_STEP и отскок.
+/// Второе значение — был ли удар о стену за этот тик
+pub fn step_grenade(body: GrenadeBody, world: &CollisionWorld) -> (GrenadeBody, bool) {
+    let mut pos = body.pos;
+    // брошена вплотную к стене — сначала выталкиваем
+    if let Some((_, corr)) = world.collide_circle(pos, GRENADE_RADIUS) {
+        pos += corr;
+    }
+
+    let mut vel = stop_if_slow(body.vel * GRENADE_DRAG_PER_TICK);
+    let mut remaining = vel * TICK_DT;
+    while remaining != Vec2::ZERO {
+        let step = remaining.clamp_length_max(MAX_STEP);
+        let proposed = pos + step;
+
+        if let Some((normal, corr)) = world.collide_circle(proposed, GRENADE_RADIUS) {
+            // отражаем скорость от нормали и гасим; остаток тика не переносим
+            pos = proposed + corr;
+            let reflected = vel - 2.0 * vel.dot(normal) * normal;
+            vel = stop_if_slow(reflected * GRENADE_RESTITUTION * GRENADE_BOUNCE_DAMPING);
+            return (GrenadeBody { pos, vel }, true);
+        }
+
+        pos = proposed;
+        remaining -= step;
+    }
+
+    (GrenadeBody { pos, vel }, false)
+}
+
+/// Прогон на `ticks` шагов вперёд
+pub fn advance_grenade(mut body: GrenadeBody, ticks: u32, world: &CollisionWorld) -> GrenadeBody {
+    for _ in 0..ticks {
+        body = step_grenade(body, world).0;
+    }
+    body
+}
+
+fn stop_if_slow(vel: Vec2) -> Vec2 {
+    if vel.length() < GRENADE_STOP_SPEED {
+        Vec2::ZERO
+    } else {
+        vel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::constants::{GRENADE_AIR_DRAG_PER_SEC, GRENADE_SPEED};
+    use protocol::map::GameMap;
+
+    // 12x8 клеток, (0,0) мира — центр
+    const MAP: &str = "\
+############
+#S.........#
+#..........#
+#....##....#
+#....##....#
+#..........#
+#..........#
+############
+";
+
+    fn world() -> CollisionWorld {
+        CollisionWorld::from_map(&GameMap::parse("test", MAP).unwrap())
+    }
+
+    fn trajectory(start: GrenadeBody, every: u32, count: usize) -> Vec<Vec2> {
+        let world = world();
+        let mut body = start;
+        (0..count)
+            .map(|_| {
+                body = advance_grenade(body, every, &world);
+                body.pos
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: &[Vec2], golden: &[(f32, f32)]) {
+        assert_eq!(actual.len(), golden.len());
+        for (i, (a, &(x, y))) in actual.iter().zip(golden).enumerate() {
+            assert!(
+                a.distance(Vec2::new(x, y)) < 1e-3,
+                "точка {i}: {a:?}, ожидали ({x}, {y})"
+            );
+        }
+    }
+
+    #[test]
+    fn drag_per_tick_matches_per_second() {
+        let exact = (1.0 - GRENADE_AIR_DRAG_PER_SEC).powf(TICK_DT);
+        assert!((GRENADE_DRAG_PER_TICK - exact).abs() < 1e-6);
+    }
+
+    #[test]
+    fn golden_free_flight() {
+        let start = GrenadeBody {
+            pos: Vec2::new(-120.0, -64.0),
+            vel: Vec2::new(GRENADE_SPEED, 0.0),
+        };
+        let golden = [(-48.565, -64.0), (21.817, -64.0), (91.161, -64.0)];
+        assert_close(&trajectory(start, 16, 3), &golden);
+    }
+
+    #[test]
+    fn golden_head_on_bounce() {
+        let start = GrenadeBody {
+            pos: Vec2::new(-120.0, 16.0),
+            vel: Vec2::new(GRENADE_SPEED, 0.0),
+        };
+        // колонна, затем левая стена
+        let golden = [
+            (-48.565, 16.0),
+            (-62.034, 16.0),
+            (-86.305, 16.0),
+            (-110.218, 16.0),
+            (-133.778, 16.0),
+            (-149.986, 16.0),
+        ];
+        assert_close(&trajectory(start, 16, 6), &golden);
+    }
+
+    #[test]
+    fn golden_corner_bounces() {
+        let start = GrenadeBody {
+            pos: Vec2::new(-100.0, -60.0),
+            vel: Vec2::new(GRENADE_SPEED, GRENADE_SPEED * 0.6),
+        };
+        // угол колонны, затем левая стена
+        let golden = [
+            (-43.605, -21.618),
+            (-68.239, -6.838),
+            (-92.509, 7.725),
+            (-116.422, 22.073),
+            (-139.982, 36.209),
+            (-147.960, 46.192),
+            (-139.956, 50.995),
+            (-132.069, 55.727),
+        ];
+        assert_close(&trajectory(start, 16, 8), &golden);
+    }
+
+    #[test]
+    fn bounce_reflects_and_damps() {
+        let world = world();
+        // летим вправо в центральную колонну
+        let mut body = GrenadeBody {
+            pos: Vec2::new(-40.0, 16.0),
+            vel: Vec2::new(GRENADE_SPEED, 0.0),
+        };
+        let bounced = loop {
+            let (next, hit) = step_grenade(body, &world);
+            if hit {
+                break next;
+            }
+            body = next;
+        };
+        assert!(bounced.vel.x < 0.0);
+        assert!(bounced.vel.y.abs() < 1e-4);
+        let damped = GRENADE_RESTITUTION * GRENADE_BOUNCE_DAMPING;
+        assert!(
+            (bounced.vel.length() - body.vel.length() * GRENADE_DRAG_PER_TICK * damped).abs()
+                < 1e-2
+        );
+        assert!(world.collide_circle(bounced.pos, GRENADE_RADIUS).is_none());
+    }
+}
@@ -0,0 +1,10 @@
+//! Детерминированная симуляция без ECS: движение игрока и полёт гранат.
+//! Сервер гоняет её в тике, клиент — в предсказании и при переигрывании.
+
+pub mod collision;
+pub mod grenade;
+pub mod movement;
+
+pub use collision::CollisionWorld;
+pub use grenade::{GrenadeBody, advance_grenade, step_grenade};
+pub use movement::{MoveState, step_player};
@@ -0,0 +1,183 @@
+use glam::Vec2;
+use protocol::constants::{PLAYER_SIZE, TICK_DT};
+use protocol::messages::InputState;
+
+use crate::collision::CollisionWorld;
+
+/// Всё, что меняет шаг движения
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MoveState {
+    pub pos: Vec2,
+    pub rot: f32,
+}
+
+/// Нормализованное направление по нажатым клавишам
+pub fn move_dir(input: &InputState) -> Vec2 {
+    let mut dir = Vec2::ZERO;
+    if input.up {
+        dir.y += 1.0;
+    }
+    if input.down {
+        dir.y -= 1.0;
+    }
+    if input.left {
+        dir.x -= 1.0;
+    }
+    if input.right {
+        dir.x += 1.0;
+    }
+    dir.normalize_or_zero()
+}
+
+/// Один тик (TICK_DT) движения: сначала по X, потом по Y — так игрок скользит вдоль стен.
+/// Скорость зависит от стойки в инпуте
+pub fn step_player(state: MoveState, input: &InputState, world: &CollisionWorld) -> MoveState {
+    let half = PLAYER_SIZE * 0.5;
+    let delta = move_dir(input) * input.stance.move_speed() * TICK_DT;
+    let mut pos = state.pos;
+
+    let proposed_x = Vec2::new(pos.x + delta.x, pos.y);
+    if !world.is_blocked(proposed_x, half) {
+        pos.x = proposed_x.x;
+    }
+    let proposed_y = Vec2::new(pos.x, pos.y + delta.y);
+    if !world.is_blocked(proposed_y, half) {
+        pos.y = proposed_y.y;
+    }
+
+    MoveState {
+        pos,
+        rot: input.rotation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use protocol::constants::MOVE_SPEED;
+    use protocol::map::GameMap;
+    use protocol::messages::Stance;
+    use std::collections::VecDeque;
+
+    const MAP: &str = "\
+##########
+#S.......#
+#..##....#
+#..#.....#
+#......#.#
+#....###.#
+#........#
+##########
+";
+
+    fn world() -> (CollisionWorld, MoveState) {
+        let map = GameMap::parse("test", MAP).unwrap();
+        let start = MoveState {
+            pos: map.tile_center(1, 1),
+            rot: 0.0,
+        };
+        (CollisionWorld::from_map(&map), start)
+    }
+
+    fn input(seq: u32, keys: u8, rotation: f32) -> InputState {
+        InputState {
+            seq,
+            up: keys & 1 != 0,
+            down: keys & 2 != 0,
+            left: keys & 4 != 0,
+            right: keys & 8 != 0,
+            rotation,
+            stance: Stance::Standing,
+            timestamp: 0.0,
+        }
+    }
+
+    fn inputs() -> impl Strategy<Value = Vec<InputState>> {
+        prop::collection::vec((0u8..16, -3.2f32..3.2), 1..200).prop_map(|keys| {
+            keys.into_iter()
+                .enumerate()
+                .map(|(i, (k, rot))| input(i as u32 + 1, k, rot))
+                .collect()
+        })
+    }
+
+    proptest! {
+        /// Сервер применяет инпуты по мере прихода, клиент предсказывает их сразу,
+        /// а на каждом «снапшоте» откатывается к серверной позиции и переигрывает хвост
+        #[test]
+        fn server_and_client_agree(inputs in inputs(), ack_every in 1usize..10, lag in 0usize..8) {
+            let (world, start) = world();
+
+            let mut server = start;
+            let mut client = start;
+            let mut pending: VecDeque<&InputState> = VecDeque::new();
+
+            for (i, inp) in inputs.iter().enumerate() {
+                client = step_player(client, inp, &world);
+                pending.push_back(inp);
+
+                // сервер отстаёт на `lag` инпутов и иногда шлёт снапшот
+                if i >= lag {
+                    let applied = &inputs[i - lag];
+                    server = step_player(server, applied, &world);
+                    if i % ack_every == 0 {
+                        while pending.front().is_some_and(|p| p.seq <= applied.seq) {
+                            pending.pop_front();
+                        }
+                        let mut replay = server;
+                        for p in &pending {
+                            replay = step_player(replay, p, &world);
+                        }
+                        prop_assert_eq!(replay, client);
+                        client = replay;
+                    }
+                }
+            }
+
+            for inp in &inputs[inputs.len().saturating_sub(lag)..] {
+                server = step_player(server, inp, &world);
+            }
+            prop_assert_eq!(server, client);
+        }
+
+        #[test]
+        fn never_ends_inside_wall(inputs in inputs()) {
+            let (world, mut state) = world();
+            for inp in &inputs {
+                let next = step_player(state, inp, &world);
+                prop_assert!(!world.is_blocked(next.pos, PLAYER_SIZE * 0.5));
+                prop_assert!(next.pos.distance(state.pos) <= MOVE_SPEED * TICK_DT + 1e-3);
+                state = next;
+            }
+        }
+    }
+
+    #[test]
+    fn slides_along_wall() {
+        // в углу у нижней стены: идём вниз-вправо — вниз нельзя, вправо можно
+        let (world, start) = world();
+        let next = step_player(start, &input(1, 2 | 8, 0.0), &world);
+        assert!(next.pos.x > start.pos.x);
+        assert_eq!(next.pos.y, start.pos.y);
+    }
+
+    #[test]
+    fn crouch_and_prone_are_slower() {
+        let (world, start) = world();
+        let step = |stance| {
+            let inp = InputState {
+                stance,
+                ..input(1, 1, 0.0)
+            };
+            step_player(start, &inp, &world).pos.distance(start.pos)
+        };
+        let (stand, crouch, prone) = (
+            step(Stance::Standing),
+            step(Stance::Crouching),
+            step(Stance::Prone),
+        );
+        assert!((stand - MOVE_SPEED * TICK_DT).abs() < 1e-4);
+        assert!(stand > crouch && crouch > prone);
+    }
+}